@@ -0,0 +1,358 @@
+//! Derive macros for custom (IANA/non-standard) components and properties of
+//! the [ics](https://crates.io/crates/ics) crate.
+//!
+//! The macros are re-exported by `ics` with the `derive` feature. The generated
+//! code refers to the `ics` crate by its name.
+//!
+//! # Properties
+//! `#[derive(IcsProperty)]` requires the fields `value: Cow<'a, str>` and
+//! `parameters: Parameters<'a>` and the name of the property. It generates the
+//! same methods as the property types of `ics` (`new`, `add` and `append`) as
+//! well as `From<T> for Property` and `TryFrom<Property> for T`. If the
+//! property has a value type other than `TEXT`, it can be set with `value`.
+//!
+//! ```
+//! use ics::components::{Parameters, Property};
+//! use ics::parameters::AltRep;
+//! use ics::IcsProperty;
+//! use std::borrow::Cow;
+//! use std::convert::TryFrom;
+//!
+//! #[derive(IcsProperty)]
+//! #[ics(name = "X-CONFERENCE", value = "URI")]
+//! pub struct Conference<'a> {
+//!     value: Cow<'a, str>,
+//!     parameters: Parameters<'a>,
+//! }
+//!
+//! let mut conference = Conference::new("https://chat.example.com/audio?id=123456");
+//! conference.add(AltRep::new("https://example.com/dial-in"));
+//! let property = Property::from(conference);
+//! assert_eq!(property.key(), "X-CONFERENCE");
+//! assert!(Conference::try_from(property).is_ok());
+//! ```
+//!
+//! # Components
+//! `#[derive(IcsComponent)]` requires the field `properties: Vec<Property<'a>>`
+//! and optionally `components: Vec<Component<'a>>` for sub-components. It
+//! generates `push` (and `add_component`) as well as `Display`,
+//! `From<T> for Component` and `TryFrom<Component> for T`.
+//!
+//! ```
+//! use ics::components::{Component, Property};
+//! use ics::properties::{Summary, UID};
+//! use ics::IcsComponent;
+//! use std::convert::TryFrom;
+//!
+//! #[derive(Default, IcsComponent)]
+//! #[ics(name = "X-VROOM")]
+//! pub struct Room<'a> {
+//!     properties: Vec<Property<'a>>,
+//! }
+//!
+//! let mut room = Room::default();
+//! room.push(UID::new("room-1"));
+//! room.push(Summary::new("Meeting room"));
+//! assert_eq!(
+//!     room.to_string(),
+//!     "BEGIN:X-VROOM\r\nUID:room-1\r\nSUMMARY:Meeting room\r\nEND:X-VROOM\r\n"
+//! );
+//! let component = Component::from(room);
+//! assert!(Room::try_from(component).is_ok());
+//! assert!(Room::try_from(Component::new("VEVENT")).is_err());
+//! ```
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Lifetime, LitStr};
+
+/// Derives the conversion from and into `Property` for a custom property.
+#[proc_macro_derive(IcsProperty, attributes(ics))]
+pub fn derive_property(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_property(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives the conversion from and into `Component` for a custom component.
+#[proc_macro_derive(IcsComponent, attributes(ics))]
+pub fn derive_component(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_component(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+// The arguments of the `#[ics(...)]` attribute
+struct Attributes {
+    name: LitStr,
+    value: Option<LitStr>,
+}
+
+fn parse_attributes(input: &DeriveInput) -> Result<Attributes, Error> {
+    let mut name = None;
+    let mut value = None;
+    for attribute in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("ics"))
+    {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("value") {
+                value = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `name` or `value`"))
+            }
+        })?;
+    }
+    match name {
+        Some(name) => Ok(Attributes { name, value }),
+        None => Err(Error::new(
+            Span::call_site(),
+            "missing `#[ics(name = \"...\")]` attribute",
+        )),
+    }
+}
+
+fn lifetime(input: &DeriveInput) -> Result<&Lifetime, Error> {
+    let mut lifetimes = input.generics.lifetimes();
+    match (
+        lifetimes.next(),
+        lifetimes.next(),
+        input.generics.type_params().next(),
+    ) {
+        (Some(param), None, None) => Ok(&param.lifetime),
+        _ => Err(Error::new_spanned(
+            &input.generics,
+            "expected exactly one lifetime parameter, e.g. `<'a>`",
+        )),
+    }
+}
+
+// Returns the names of the fields and checks that the required fields exist.
+fn field_names(input: &DeriveInput, required: &[&str]) -> Result<Vec<String>, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, "expected named fields")),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "expected a struct")),
+    };
+    let names: Vec<String> = fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref().map(ToString::to_string))
+        .collect();
+    for &name in required {
+        if !names.iter().any(|field| field == name) {
+            let message = format!("missing field `{}`", name);
+            return Err(Error::new_spanned(&input.ident, message));
+        }
+    }
+    Ok(names)
+}
+
+fn expand_property(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let attributes = parse_attributes(input)?;
+    let lifetime = lifetime(input)?;
+    let names = field_names(input, &["value", "parameters"])?;
+    if names.len() > 2 {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "expected only the fields `value` and `parameters`",
+        ));
+    }
+    let ident = &input.ident;
+    let name = &attributes.name;
+    let parameters = match &attributes.value {
+        Some(value) => quote!(::ics::parameters!("VALUE" => #value)),
+        None => quote!(::std::collections::BTreeMap::new()),
+    };
+
+    Ok(quote! {
+        impl<#lifetime> #ident<#lifetime> {
+            #[doc = "Creates a new `"]
+            #[doc = #name]
+            #[doc = "` Property with the given value."]
+            pub fn new<S>(value: S) -> Self
+            where
+                S: ::std::convert::Into<::std::borrow::Cow<#lifetime, str>>,
+            {
+                Self {
+                    value: value.into(),
+                    parameters: #parameters,
+                }
+            }
+
+            /// Adds a parameter to the property. Values of multi-valued
+            /// parameters are added to the existing values, other parameters
+            /// are replaced.
+            pub fn add<P>(&mut self, parameter: P)
+            where
+                P: ::std::convert::Into<::ics::components::Parameter<#lifetime>>,
+            {
+                ::ics::components::insert_parameter(&mut self.parameters, parameter.into());
+            }
+
+            /// Adds several parameters at once to the property. Values of
+            /// multi-valued parameters are merged like with `add`.
+            pub fn append(&mut self, parameters: ::ics::components::Parameters<#lifetime>) {
+                for (key, value) in parameters {
+                    ::ics::components::insert_parameter(
+                        &mut self.parameters,
+                        ::ics::components::Parameter::new(key, value),
+                    );
+                }
+            }
+        }
+
+        impl<#lifetime> ::std::convert::From<#ident<#lifetime>>
+            for ::ics::components::Property<#lifetime>
+        {
+            fn from(builder: #ident<#lifetime>) -> Self {
+                let mut property = ::ics::components::Property::new(#name, builder.value);
+                property.append(builder.parameters);
+                property
+            }
+        }
+
+        impl<#lifetime> ::std::convert::TryFrom<::ics::components::Property<#lifetime>>
+            for #ident<#lifetime>
+        {
+            type Error = ::ics::validation::Error;
+
+            fn try_from(
+                property: ::ics::components::Property<#lifetime>,
+            ) -> ::std::result::Result<Self, Self::Error> {
+                let (key, value, parameters) = property.into_parts();
+                if !key.eq_ignore_ascii_case(#name) {
+                    return Err(::ics::validation::Error::InvalidFormat { format: #name });
+                }
+                Ok(Self { value, parameters })
+            }
+        }
+    })
+}
+
+fn expand_component(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let attributes = parse_attributes(input)?;
+    if let Some(value) = &attributes.value {
+        return Err(Error::new_spanned(value, "components have no value type"));
+    }
+    let lifetime = lifetime(input)?;
+    let names = field_names(input, &["properties"])?;
+    let has_components = names.iter().any(|name| name == "components");
+    if names.len() > 1 + has_components as usize {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "expected only the fields `properties` and `components`",
+        ));
+    }
+    let ident = &input.ident;
+    let name = &attributes.name;
+
+    let add_component = if has_components {
+        quote! {
+            /// Adds a sub-component to the component.
+            pub fn add_component<C>(&mut self, component: C)
+            where
+                C: ::std::convert::Into<::ics::components::Component<#lifetime>>,
+            {
+                self.components.push(component.into());
+            }
+        }
+    } else {
+        TokenStream2::new()
+    };
+    let (write_components, add_components, take_components) = if has_components {
+        (
+            quote! {
+                for component in &self.components {
+                    write!(f, "{}", component)?;
+                }
+            },
+            quote! {
+                for subcomponent in builder.components {
+                    component.add_component(subcomponent);
+                }
+            },
+            TokenStream2::new(),
+        )
+    } else {
+        (
+            TokenStream2::new(),
+            TokenStream2::new(),
+            quote! {
+                if !components.is_empty() {
+                    return Err(::ics::validation::Error::InvalidFormat { format: #name });
+                }
+            },
+        )
+    };
+    let fields = if has_components {
+        quote!(properties, components)
+    } else {
+        quote!(properties)
+    };
+
+    Ok(quote! {
+        impl<#lifetime> #ident<#lifetime> {
+            /// Adds a property to the component.
+            pub fn push<P>(&mut self, property: P)
+            where
+                P: ::std::convert::Into<::ics::components::Property<#lifetime>>,
+            {
+                self.properties.push(property.into());
+            }
+
+            #add_component
+        }
+
+        impl<#lifetime> ::std::fmt::Display for #ident<#lifetime> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                writeln!(f, "BEGIN:{}\r", #name)?;
+                for property in &self.properties {
+                    write!(f, "{}", property)?;
+                }
+                #write_components
+                writeln!(f, "END:{}\r", #name)
+            }
+        }
+
+        impl<#lifetime> ::std::convert::From<#ident<#lifetime>>
+            for ::ics::components::Component<#lifetime>
+        {
+            fn from(builder: #ident<#lifetime>) -> Self {
+                let mut component = ::ics::components::Component::new(#name);
+                for property in builder.properties {
+                    component.add_property(property);
+                }
+                #add_components
+                component
+            }
+        }
+
+        impl<#lifetime> ::std::convert::TryFrom<::ics::components::Component<#lifetime>>
+            for #ident<#lifetime>
+        {
+            type Error = ::ics::validation::Error;
+
+            fn try_from(
+                component: ::ics::components::Component<#lifetime>,
+            ) -> ::std::result::Result<Self, Self::Error> {
+                let (name, properties, components) = component.into_parts();
+                if !name.eq_ignore_ascii_case(#name) {
+                    return Err(::ics::validation::Error::InvalidFormat { format: #name });
+                }
+                #take_components
+                Ok(Self { #fields })
+            }
+        }
+    })
+}
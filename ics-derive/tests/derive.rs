@@ -0,0 +1,107 @@
+use ics::components::{Component, Parameters, Property};
+use ics::parameters::{Member, Value};
+use ics::properties::{Summary, UID};
+use ics::validation::Error;
+use ics::{Alarm, IcsComponent, IcsProperty};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+#[derive(Debug, PartialEq, IcsProperty)]
+#[ics(name = "X-ROOM-NUMBER", value = "INTEGER")]
+struct RoomNumber<'a> {
+    value: Cow<'a, str>,
+    parameters: Parameters<'a>,
+}
+
+#[derive(Debug, PartialEq, IcsProperty)]
+#[ics(name = "X-SPEAKER")]
+struct Speaker<'a> {
+    value: Cow<'a, str>,
+    parameters: Parameters<'a>,
+}
+
+#[derive(Debug, Default, PartialEq, IcsComponent)]
+#[ics(name = "X-VROOM")]
+struct Room<'a> {
+    properties: Vec<Property<'a>>,
+    components: Vec<Component<'a>>,
+}
+
+#[derive(Debug, Default, PartialEq, IcsComponent)]
+#[ics(name = "X-VSEAT")]
+struct Seat<'a> {
+    properties: Vec<Property<'a>>,
+}
+
+#[test]
+fn property() {
+    let number = RoomNumber::new("42");
+    assert_eq!(
+        Property::from(number).to_string(),
+        "X-ROOM-NUMBER;VALUE=INTEGER:42\r\n"
+    );
+
+    let mut speaker = Speaker::new("Jane Doe");
+    speaker.add(Member::new("mailto:a@example.com"));
+    speaker.add(Member::new("mailto:b@example.com"));
+    let property = Property::from(speaker);
+    assert_eq!(
+        property.to_string(),
+        "X-SPEAKER;MEMBER=\"mailto:a@example.com\",\"mailto:b@example.com\":Jane Doe\r\n"
+    );
+    let mut appended = Speaker::new("Jane Doe");
+    appended.add(Member::new("mailto:a@example.com"));
+    appended.append(ics::parameters!("MEMBER" => "mailto:b@example.com"));
+    assert_eq!(Property::from(appended), property);
+    let speaker = Speaker::try_from(property.clone()).unwrap();
+    assert_eq!(Property::from(speaker), property);
+
+    let mut lowercase = Property::new("x-speaker", "John Doe");
+    lowercase.add(Value::TEXT);
+    assert!(Speaker::try_from(lowercase).is_ok());
+    assert_eq!(
+        Speaker::try_from(Property::new("SUMMARY", "Talk")),
+        Err(Error::InvalidFormat {
+            format: "X-SPEAKER"
+        })
+    );
+}
+
+#[test]
+fn component() {
+    let mut room = Room::default();
+    room.push(UID::new("room-1"));
+    room.push(RoomNumber::new("42"));
+    room.add_component(Alarm::display(
+        ics::properties::Trigger::new("-PT5M"),
+        ics::properties::Description::new("Meeting starts"),
+    ));
+    let expected = concat!(
+        "BEGIN:X-VROOM\r\n",
+        "UID:room-1\r\n",
+        "X-ROOM-NUMBER;VALUE=INTEGER:42\r\n",
+        "BEGIN:VALARM\r\n",
+        "ACTION:DISPLAY\r\n",
+        "TRIGGER:-PT5M\r\n",
+        "DESCRIPTION:Meeting starts\r\n",
+        "END:VALARM\r\n",
+        "END:X-VROOM\r\n"
+    );
+    assert_eq!(room.to_string(), expected);
+
+    let component = Component::from(room);
+    assert_eq!(component.to_string(), expected);
+    let room = Room::try_from(component.clone()).unwrap();
+    assert_eq!(Component::from(room), component);
+    assert!(Seat::try_from(component).is_err());
+
+    let mut seat = Seat::default();
+    seat.push(Summary::new("Window seat"));
+    assert_eq!(
+        Seat::try_from(Component::from(seat))
+            .unwrap()
+            .properties
+            .len(),
+        1
+    );
+}
@@ -18,7 +18,17 @@
 //!     }
 //! }
 //! ```
+//!
+//! With the `derive` feature, the `IcsComponent` and `IcsProperty` derive
+//! macros generate the conversions from and into `Component` and `Property`
+//! as well as the builder methods of the built-in types.
 use crate::contentline;
+use crate::parameters::Value;
+use crate::util::{
+    decode_param_value, is_quoted, quote_param_list_item, quote_param_value, split_param_values,
+    unquote_param_value,
+};
+use crate::validation::{self, ValueType};
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::fmt;
@@ -28,9 +38,13 @@ use std::fmt;
 /// This can be used to create a new calendar component by either creating a
 /// wrapper type or just use it as it is.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Component<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub(crate) name: Cow<'a, str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub(crate) properties: Vec<Property<'a>>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub(crate) subcomponents: Vec<Component<'a>>,
 }
 
@@ -63,6 +77,11 @@ impl<'a> Component<'a> {
     {
         self.subcomponents.push(component.into());
     }
+
+    /// Splits the component into its name, properties and sub-components.
+    pub fn into_parts(self) -> (Cow<'a, str>, Vec<Property<'a>>, Vec<Component<'a>>) {
+        (self.name, self.properties, self.subcomponents)
+    }
 }
 
 impl<'a> fmt::Display for Component<'a> {
@@ -85,9 +104,13 @@ impl<'a> fmt::Display for Component<'a> {
 /// new calendar property by either creating a wrapper type or just use it as
 /// it is.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Property<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub(crate) key: Cow<'a, str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub(crate) value: Cow<'a, str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub(crate) parameters: Parameters<'a>,
 }
 
@@ -105,20 +128,154 @@ impl<'a> Property<'a> {
         }
     }
 
-    /// Adds a parameter to a property.
+    /// Creates a new property with a `BOOLEAN` value (`TRUE` or `FALSE`) and
+    /// the `VALUE=BOOLEAN` parameter.
+    ///
+    /// # Example
+    /// ```
+    /// use ics::components::Property;
+    ///
+    /// let property = Property::boolean("X-ALLOW-COUNTER", false);
+    /// assert_eq!(property.to_string(), "X-ALLOW-COUNTER;VALUE=BOOLEAN:FALSE\r\n");
+    /// ```
+    pub fn boolean<K>(key: K, value: bool) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+    {
+        let value = if value { "TRUE" } else { "FALSE" };
+        Self::with_value_type(key, value, Value::BOOLEAN)
+    }
+
+    /// Creates a new property with an `INTEGER` value and the `VALUE=INTEGER`
+    /// parameter.
+    pub fn integer<K>(key: K, value: i32) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+    {
+        Self::with_value_type(key, value.to_string(), Value::INTEGER)
+    }
+
+    /// Creates a new property with a `FLOAT` value and the `VALUE=FLOAT`
+    /// parameter. Infinite values and NaN cannot be represented and return an
+    /// error.
+    ///
+    /// # Example
+    /// ```
+    /// use ics::components::Property;
+    ///
+    /// let property = Property::float("X-RATING", 4.5).unwrap();
+    /// assert_eq!(property.to_string(), "X-RATING;VALUE=FLOAT:4.5\r\n");
+    /// assert!(Property::float("X-RATING", std::f64::NAN).is_err());
+    /// ```
+    pub fn float<K>(key: K, value: f64) -> Result<Self, validation::Error>
+    where
+        K: Into<Cow<'a, str>>,
+    {
+        let key = key.into();
+        if !value.is_finite() {
+            return Err(invalid_value(&key, ValueType::Float));
+        }
+        // avoid writing -0
+        let value = if value == 0.0 { 0.0 } else { value };
+        Ok(Self::with_value_type(key, value.to_string(), Value::FLOAT))
+    }
+
+    /// Creates a new property with a local `TIME` value and the `VALUE=TIME`
+    /// parameter. The second can be 60 to represent a leap second.
+    ///
+    /// # Example
+    /// ```
+    /// use ics::components::Property;
+    ///
+    /// let property = Property::time("X-OPENING", 8, 30, 0).unwrap();
+    /// assert_eq!(property.to_string(), "X-OPENING;VALUE=TIME:083000\r\n");
+    /// ```
+    pub fn time<K>(key: K, hour: u8, minute: u8, second: u8) -> Result<Self, validation::Error>
+    where
+        K: Into<Cow<'a, str>>,
+    {
+        Self::format_time(key.into(), hour, minute, second, "")
+    }
+
+    /// Creates a new property with a `TIME` value in UTC (ending with `Z`) and
+    /// the `VALUE=TIME` parameter.
+    pub fn utc_time<K>(key: K, hour: u8, minute: u8, second: u8) -> Result<Self, validation::Error>
+    where
+        K: Into<Cow<'a, str>>,
+    {
+        Self::format_time(key.into(), hour, minute, second, "Z")
+    }
+
+    fn format_time(
+        key: Cow<'a, str>,
+        hour: u8,
+        minute: u8,
+        second: u8,
+        suffix: &str,
+    ) -> Result<Self, validation::Error> {
+        if hour >= 24 || minute >= 60 || second > 60 {
+            return Err(invalid_value(&key, ValueType::Time));
+        }
+        let value = format!("{:02}{:02}{:02}{}", hour, minute, second, suffix);
+        Ok(Self::with_value_type(key, value, Value::TIME))
+    }
+
+    fn with_value_type<K, V>(key: K, value: V, value_type: Value<'a>) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        let mut property = Self::new(key, value);
+        property.add(value_type);
+        property
+    }
+
+    /// Adds a parameter to a property. Values of multi-valued parameters
+    /// (`MEMBER`, `DELEGATED-TO` and `DELEGATED-FROM`) are added to the
+    /// existing values, other parameters are replaced.
     pub fn add<P>(&mut self, parameter: P)
     where
         P: Into<Parameter<'a>>,
     {
-        let parameter = parameter.into();
-        self.parameters.insert(parameter.key, parameter.value);
+        insert_parameter(&mut self.parameters, parameter.into());
     }
 
-    /// Adds several parameters at once to a property. For creating several
+    /// Adds several parameters at once to a property. Values of multi-valued
+    /// parameters are merged like with `add`. For creating several
     /// parameters at once, consult the documentation of the `parameters!`
     /// macro.
-    pub fn append(&mut self, mut parameters: Parameters<'a>) {
-        self.parameters.append(&mut parameters);
+    pub fn append(&mut self, parameters: Parameters<'a>) {
+        for (key, value) in parameters {
+            insert_parameter(&mut self.parameters, Parameter { key, value });
+        }
+    }
+
+    /// Returns the name of the property.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the value of the property.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns the parameters of the property.
+    pub fn parameters(&self) -> &Parameters<'a> {
+        &self.parameters
+    }
+
+    /// Returns the parameter with the given name if the property has it.
+    pub fn parameter(&self, key: &str) -> Option<Parameter<'_>> {
+        self.parameters
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(key, value)| Parameter::new(key.as_ref(), value.as_ref()))
+    }
+
+    /// Splits the property into its name, value and parameters.
+    pub fn into_parts(self) -> (Cow<'a, str>, Cow<'a, str>, Parameters<'a>) {
+        (self.key, self.value, self.parameters)
     }
 
     fn content_len(&self) -> usize {
@@ -127,19 +284,26 @@ impl<'a> Property<'a> {
         self.parameters
             .iter()
             .fold(self.value.len() + self.key.len() + 1, |len, (k, v)| {
-                len + k.len() + v.len() + 2
+                len + k.len() + quote_param_value(k, v).len() + 2
             })
     }
 
     fn format<W: fmt::Write>(&self, writer: &mut W) -> fmt::Result {
         write!(writer, "{}", self.key)?;
         for (key, value) in &self.parameters {
-            write!(writer, ";{}={}", key, value)?;
+            write!(writer, ";{}={}", key, quote_param_value(key, value))?;
         }
         write!(writer, ":{}", self.value)
     }
 }
 
+fn invalid_value(property: &str, value_type: ValueType) -> validation::Error {
+    validation::Error::InvalidValue {
+        property: property.to_string(),
+        value_type,
+    }
+}
+
 impl<'a> fmt::Display for Property<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let len = self.content_len();
@@ -157,11 +321,20 @@ impl<'a> fmt::Display for Property<'a> {
 /// A `Parameter` is a key-value that can be added to a property to specify it
 /// more.
 ///
+/// Values containing a colon, semicolon or comma are quoted automatically when
+/// they are written. DQUOTE characters and newlines are not allowed in
+/// parameter values. With the `rfc6868` feature they are encoded as specified
+/// in [RFC6868](https://tools.ietf.org/html/rfc6868), otherwise they are
+/// removed.
+///
 /// This can be used to create a new calendar parameter by either creating a
 /// wrapper type or just use it as it is.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parameter<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub(crate) key: Cow<'a, str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub(crate) value: Cow<'a, str>,
 }
 
@@ -177,11 +350,84 @@ impl<'a> Parameter<'a> {
             value: value.into(),
         }
     }
+
+    /// Parses a parameter from its textual representation `KEY=VALUE`. The
+    /// quotes around the value are removed and, with the `rfc6868` feature,
+    /// the caret encoding is decoded.
+    ///
+    /// # Example
+    /// ```
+    /// use ics::components::Parameter;
+    ///
+    /// let parameter = Parameter::parse("SENT-BY=\"mailto:sray@example.com\"");
+    /// assert_eq!(parameter, Some(Parameter::new("SENT-BY", "mailto:sray@example.com")));
+    /// ```
+    pub fn parse(input: &'a str) -> Option<Self> {
+        let mut pair = input.splitn(2, '=');
+        match (pair.next(), pair.next()) {
+            (Some(key), Some(value)) if !key.is_empty() => {
+                let value = if is_multi_valued(key) {
+                    let values: Vec<_> = split_param_values(value)
+                        .iter()
+                        .map(|value| quote_param_list_item(value))
+                        .collect();
+                    Cow::Owned(values.join(","))
+                } else {
+                    decode_param_value(unquote_param_value(value))
+                };
+                Some(Parameter {
+                    key: Cow::Borrowed(key),
+                    value,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the parameter.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the value of the parameter as it is written.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns the unquoted values of the parameter. Multi-valued parameters
+    /// like `MEMBER` contain a comma separated list of quoted values, other
+    /// parameters have only one value. The values are returned as they were
+    /// added; only `parse` decodes the caret encoding.
+    ///
+    /// # Example
+    /// ```
+    /// use ics::components::Property;
+    /// use ics::parameters::DelegatedTo;
+    ///
+    /// let mut attendee = Property::new("ATTENDEE", "mailto:jsmith@example.com");
+    /// attendee.add(DelegatedTo::new("mailto:jdoe@example.com"));
+    /// attendee.add(DelegatedTo::new("mailto:jqpublic@example.com"));
+    ///
+    /// let delegates = attendee.parameter("DELEGATED-TO").unwrap();
+    /// assert_eq!(delegates.values(), vec!["mailto:jdoe@example.com", "mailto:jqpublic@example.com"]);
+    /// ```
+    pub fn values(&self) -> Vec<Cow<'_, str>> {
+        if is_multi_valued(&self.key) {
+            split_param_values(&self.value)
+        } else {
+            vec![Cow::Borrowed(&self.value)]
+        }
+    }
 }
 
 impl<'a> fmt::Display for Parameter<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}={}", self.key, self.value)
+        write!(
+            f,
+            "{}={}",
+            self.key,
+            quote_param_value(&self.key, &self.value)
+        )
     }
 }
 
@@ -189,6 +435,57 @@ impl<'a> fmt::Display for Parameter<'a> {
 /// `parameters!` macro.
 pub type Parameters<'a> = BTreeMap<Cow<'a, str>, Cow<'a, str>>;
 
+const MULTI_VALUED_PARAMETERS: [&str; 3] = ["MEMBER", "DELEGATED-TO", "DELEGATED-FROM"];
+
+pub(crate) fn is_multi_valued(key: &str) -> bool {
+    MULTI_VALUED_PARAMETERS
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(key))
+}
+
+// Parameter names are case-insensitive.
+pub(crate) fn find_parameter<'p, 'a>(
+    parameters: &'p Parameters<'a>,
+    key: &str,
+) -> Option<&'p Cow<'a, str>> {
+    parameters
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value)
+}
+
+/// Inserts a parameter into the collection. Values of multi-valued parameters
+/// (`MEMBER`, `DELEGATED-TO` and `DELEGATED-FROM`) are added to the list of
+/// existing values instead of replacing them.
+pub fn insert_parameter<'a>(parameters: &mut Parameters<'a>, parameter: Parameter<'a>) {
+    // A single value may not be quoted yet
+    fn as_list(value: &str) -> Cow<'_, str> {
+        if is_quoted(value) {
+            Cow::Borrowed(value)
+        } else {
+            Cow::Owned(quote_param_list_item(value))
+        }
+    }
+
+    if !is_multi_valued(&parameter.key) {
+        parameters.insert(parameter.key, parameter.value);
+        return;
+    }
+    let existing = parameters
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(&parameter.key))
+        .map(|(name, values)| (name.clone(), values));
+    match existing {
+        Some((key, values)) => {
+            let value = format!("{},{}", as_list(values), as_list(&parameter.value));
+            parameters.insert(key, Cow::Owned(value));
+        }
+        None => {
+            parameters.insert(parameter.key, parameter.value);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Parameter, Property};
@@ -207,4 +504,85 @@ mod tests {
         let expected = 25;
         assert_eq!(property.content_len(), expected);
     }
+
+    #[test]
+    fn quoted_parameter() {
+        let mut property = Property::new("ATTENDEE", "mailto:jsmith@example.com");
+        property.add(Parameter::new("SENT-BY", "mailto:sray@example.com"));
+        let expected = "ATTENDEE;SENT-BY=\"mailto:sray@example.com\":mailto:jsmith@example.com\r\n";
+        assert_eq!(property.content_len(), expected.len() - 2);
+        assert_eq!(property.to_string(), expected);
+    }
+
+    #[test]
+    fn multi_valued_parameter() {
+        let mut property = Property::new("ATTENDEE", "mailto:jsmith@example.com");
+        property.add(Parameter::new("MEMBER", "mailto:a@example.com"));
+        property.add(Parameter::new("MEMBER", "mailto:b@example.com"));
+        assert_eq!(
+            property.to_string(),
+            "ATTENDEE;MEMBER=\"mailto:a@example.com\",\"mailto:b@example.com\":mailto:jsmith\r\n @example.com\r\n"
+        );
+        assert!(property.parameter("member").is_some());
+
+        let mut appended = Property::new("ATTENDEE", "mailto:jsmith@example.com");
+        appended.add(Parameter::new("MEMBER", "mailto:a@example.com"));
+        appended.append(parameters!("MEMBER" => "mailto:b@example.com"));
+        assert_eq!(appended, property);
+        assert!(crate::parameters::Member::from_list(Vec::<&str>::new()).is_none());
+
+        let mut mixed_case = Property::new("ATTENDEE", "mailto:jsmith@example.com");
+        mixed_case.add(Parameter::new("member", "mailto:a@example.com"));
+        mixed_case.add(crate::parameters::Member::new("mailto:b@example.com"));
+        assert_eq!(mixed_case.parameters().len(), 1);
+        assert_eq!(
+            mixed_case.parameter("MEMBER").unwrap().values(),
+            vec!["mailto:a@example.com", "mailto:b@example.com"]
+        );
+    }
+
+    #[test]
+    fn parameter_values() {
+        let parameter = Parameter::new("CN", "Smiley ^^ face");
+        assert_eq!(parameter.values(), vec!["Smiley ^^ face"]);
+
+        let parameter =
+            Parameter::parse("MEMBER=\"mailto:a@example.com\",\"mailto:b@example.com\"");
+        assert_eq!(
+            parameter.unwrap().values(),
+            vec!["mailto:a@example.com", "mailto:b@example.com"]
+        );
+    }
+
+    #[cfg(feature = "rfc6868")]
+    #[test]
+    fn parse_caret_encoding() {
+        let parameter = Parameter::parse("CN=\"Smiley ^^ ^'face^'\"").unwrap();
+        assert_eq!(parameter.value(), "Smiley ^ \"face\"");
+        assert_eq!(parameter.values(), vec!["Smiley ^ \"face\""]);
+        assert_eq!(parameter.to_string(), "CN=\"Smiley ^^ ^'face^'\"");
+
+        let parameter = Parameter::parse("MEMBER=\"mailto:a^^b@example.com\"").unwrap();
+        assert_eq!(parameter.values(), vec!["mailto:a^b@example.com"]);
+        assert_eq!(parameter.to_string(), "MEMBER=\"mailto:a^^b@example.com\"");
+    }
+
+    #[test]
+    fn typed_values() {
+        assert_eq!(
+            Property::integer("X-COUNT", -3).to_string(),
+            "X-COUNT;VALUE=INTEGER:-3\r\n"
+        );
+        assert_eq!(Property::float("X-F", -0.0).unwrap().value(), "0");
+        assert_eq!(
+            Property::float("X-F", 1e21).unwrap().value(),
+            "1000000000000000000000"
+        );
+        assert_eq!(
+            Property::utc_time("X-T", 23, 59, 60).unwrap().value(),
+            "235960Z"
+        );
+        assert!(Property::time("X-T", 24, 0, 0).is_err());
+        assert!(Property::float("X-F", std::f64::INFINITY).is_err());
+    }
 }
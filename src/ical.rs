@@ -3,6 +3,7 @@ use crate::properties::{
     Action, Description, DtStamp, DtStart, ProdID, Summary, Trigger, TzID, TzOffsetFrom,
     TzOffsetTo, Version, UID,
 };
+use crate::{jcal, xcal};
 use std::borrow::Cow;
 use std::fmt;
 use std::fs::File;
@@ -18,7 +19,8 @@ use std::path::Path;
 /// where the specified components are added. To save the object as file, it
 /// needs to be written to a file.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct ICalendar<'a>(Component<'a>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ICalendar<'a>(#[cfg_attr(feature = "serde", serde(borrow))] pub(crate) Component<'a>);
 
 impl<'a> ICalendar<'a> {
     /// Creates a new iCalendar object/`VCALENDAR` calendar component. The
@@ -95,6 +97,48 @@ impl<'a> ICalendar<'a> {
     {
         self.write(File::create(filename)?)
     }
+
+    /// Converts the iCalendar object into jCal, the JSON format for iCalendar
+    /// specified in [RFC7265](https://tools.ietf.org/html/rfc7265). See the
+    /// [`jcal`](jcal/index.html) module for details.
+    pub fn to_jcal(&self) -> String {
+        jcal::to_string(&self.0)
+    }
+
+    /// Converts the iCalendar object into xCal, the XML format for iCalendar
+    /// specified in [RFC6321](https://tools.ietf.org/html/rfc6321). See the
+    /// [`xcal`](xcal/index.html) module for details.
+    pub fn to_xcal(&self) -> String {
+        xcal::to_string(&self.0)
+    }
+
+    /// Reads an iCalendar object from jCal, the JSON format for iCalendar
+    /// specified in [RFC7265](https://tools.ietf.org/html/rfc7265). The
+    /// top-level component must be a `vcalendar` component.
+    pub fn from_jcal(input: &'a str) -> Result<Self, jcal::Error> {
+        let component = jcal::from_str(input)?;
+        if component.name.eq_ignore_ascii_case("VCALENDAR") {
+            Ok(ICalendar(component))
+        } else {
+            Err(jcal::Error::InvalidStructure {
+                expected: "vcalendar component",
+            })
+        }
+    }
+
+    /// Reads an iCalendar object from xCal, the XML format for iCalendar
+    /// specified in [RFC6321](https://tools.ietf.org/html/rfc6321). The
+    /// `icalendar` element must contain one `vcalendar` component.
+    pub fn from_xcal(input: &'a str) -> Result<Self, xcal::Error> {
+        let component = xcal::from_str(input)?;
+        if component.name.eq_ignore_ascii_case("VCALENDAR") {
+            Ok(ICalendar(component))
+        } else {
+            Err(xcal::Error::InvalidStructure {
+                expected: "vcalendar component",
+            })
+        }
+    }
 }
 
 impl<'a> fmt::Display for ICalendar<'a> {
@@ -115,9 +159,12 @@ impl<'a> From<ICalendar<'a>> for Component<'a> {
 /// including an [`Alarm`], that represents a scheduled amount of time on a
 /// calendar. (see [RFC5545 3.6.1. Event Component](https://tools.ietf.org/html/rfc5545#section-3.6.1))
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Event<'a> {
-    properties: Vec<Property<'a>>,
-    alarms: Vec<Alarm<'a>>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub(crate) properties: Vec<Property<'a>>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub(crate) alarms: Vec<Alarm<'a>>,
 }
 
 impl<'a> Event<'a> {
@@ -175,9 +222,12 @@ impl<'a> From<Event<'a>> for Component<'a> {
 /// A [`ToDo`] component is a grouping of component properties, possibly
 /// including an [`Alarm`], that represent an action-item or assignment. (see [RFC5545 3.6.2. To-Do Component](https://tools.ietf.org/html/rfc5545#section-3.6.2))
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ToDo<'a> {
-    properties: Vec<Property<'a>>,
-    alarms: Vec<Alarm<'a>>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub(crate) properties: Vec<Property<'a>>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub(crate) alarms: Vec<Alarm<'a>>,
 }
 
 impl<'a> ToDo<'a> {
@@ -239,7 +289,8 @@ impl<'a> From<ToDo<'a>> for Component<'a> {
 /// one or more descriptive text notes associated with a particular calendar
 /// date. (see [RFC5545 3.6.3. Journal Component](https://tools.ietf.org/html/rfc5545#section-3.6.3))
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct Journal<'a>(Vec<Property<'a>>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Journal<'a>(#[cfg_attr(feature = "serde", serde(borrow))] Vec<Property<'a>>);
 
 impl<'a> Journal<'a> {
     /// Creates a new `VJOURNAL` calendar component. The `UID` and `DTSTAMP`
@@ -290,7 +341,8 @@ impl<'a> From<Journal<'a>> for Component<'a> {
 /// request for free or busy time information, or a published set of busy time
 /// information. (see [RFC5545 3.6.4. Free/Busy Component Component](https://tools.ietf.org/html/rfc5545#section-3.6.4))
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct FreeBusy<'a>(Vec<Property<'a>>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FreeBusy<'a>(#[cfg_attr(feature = "serde", serde(borrow))] Vec<Property<'a>>);
 
 impl<'a> FreeBusy<'a> {
     /// Creates a new `VFREEBUSY` calendar component. The `UID` and `DTSTAMP`
@@ -340,8 +392,11 @@ impl<'a> From<FreeBusy<'a>> for Component<'a> {
 /// measurement rules ([`Standard`] or [`Daylight`]) determined by the governing
 /// body for a given geographic area. (see [RFC5545 3.6.5. Time Zone Component Component](https://tools.ietf.org/html/rfc5545#section-3.6.5))
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeZone<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
     properties: Vec<Property<'a>>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     zone_times: Vec<ZoneTime<'a>>,
 }
 
@@ -422,11 +477,12 @@ impl<'a> From<TimeZone<'a>> for Component<'a> {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum ZoneTime<'a> {
     /// Standard Time
-    Standard(Standard<'a>),
+    Standard(#[cfg_attr(feature = "serde", serde(borrow))] Standard<'a>),
     /// Daylight Saving Time
-    Daylight(Daylight<'a>),
+    Daylight(#[cfg_attr(feature = "serde", serde(borrow))] Daylight<'a>),
 }
 
 impl<'a> fmt::Display for ZoneTime<'a> {
@@ -452,11 +508,15 @@ impl<'a> From<ZoneTime<'a>> for Component<'a> {
 /// A [`Standard`] component is a sub-component of the [`TimeZone`] component
 /// which describes rules for standard time, also known as winter time.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct Standard<'a>(Vec<Property<'a>>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Standard<'a>(#[cfg_attr(feature = "serde", serde(borrow))] Vec<Property<'a>>);
 
 impl<'a> Standard<'a> {
     /// Creates a new `STANDARD` sub-component. The properties `DTSTART`,
     /// `TZOFFSETFROM` and `TZOFFSETTO` are required.
+    ///
+    /// The offsets can be passed as [`UtcOffset`](values/struct.UtcOffset.html)
+    /// which makes sure that they are valid.
     pub fn new<S, T, F>(dtstart: S, tz_offset_from: F, tz_offset_to: T) -> Self
     where
         S: Into<Cow<'a, str>>,
@@ -506,11 +566,15 @@ impl<'a> From<Standard<'a>> for Component<'a> {
 /// which describes rules for daylight saving time, also known as advanced time,
 /// summer time, or legal time in certain countries.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct Daylight<'a>(Vec<Property<'a>>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Daylight<'a>(#[cfg_attr(feature = "serde", serde(borrow))] Vec<Property<'a>>);
 
 impl<'a> Daylight<'a> {
     /// Creates a new `DAYLIGHT` sub-component. The properties `DTSTART`,
     /// `TZOFFSETFROM` and `TZOFFSETTO` are required.
+    ///
+    /// The offsets can be passed as [`UtcOffset`](values/struct.UtcOffset.html)
+    /// which makes sure that they are valid.
     pub fn new<S, T, F>(dtstart: S, tz_offset_from: F, tz_offset_to: T) -> Self
     where
         S: Into<Cow<'a, str>>,
@@ -560,7 +624,8 @@ impl<'a> From<Daylight<'a>> for Component<'a> {
 /// reminder or alarm for an [`Event`] or a [`ToDo`]. For example, it may be
 /// used to define a reminder for a pending event or an overdue to-do. (see [RFC5545 3.6.6. Alarm Component](https://tools.ietf.org/html/rfc5545#section-3.6.6))
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct Alarm<'a>(Vec<Property<'a>>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Alarm<'a>(#[cfg_attr(feature = "serde", serde(borrow))] pub(crate) Vec<Property<'a>>);
 
 // The specific constructors use the specific property builder types since the
 // required properties can have defined parameters.
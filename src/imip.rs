@@ -0,0 +1,459 @@
+//! Email messages for scheduling as specified in [RFC6047](https://tools.ietf.org/html/rfc6047) (iMIP).
+//!
+//! This module requires the `imip` feature. A `Message` wraps a calendar with
+//! a `METHOD` property, e.g. created with the `itip` module, into a
+//! `multipart/alternative` MIME message with a plain text part and a
+//! `text/calendar` part. The message is rendered as bytes and can be sent with
+//! any SMTP library.
+//!
+//! Parts that only contain ASCII characters and lines of at most 998 octets
+//! are sent as `7bit`, which is always the case for calendars because content
+//! lines are folded after 75 octets. Other parts are encoded with `base64`.
+//! Non-ASCII subjects and display names are written as encoded words
+//! ([RFC2047](https://tools.ietf.org/html/rfc2047)).
+//!
+//! # Example
+//! ```
+//! use ics::imip::Message;
+//! use ics::properties::Method;
+//! use ics::ICalendar;
+//!
+//! let mut calendar = ICalendar::new("2.0", "-//Example//EN");
+//! calendar.push(Method::new("REQUEST"));
+//!
+//! let mut message = Message::new(
+//!     "Jane Doe <jane@example.com>",
+//!     "Invitation: Meeting",
+//!     "Tue, 1 Jan 2019 10:00:00 +0000",
+//!     calendar,
+//! );
+//! message.to.push("john@example.com".into());
+//! message.text = "You are invited to a meeting.".into();
+//!
+//! let bytes = message.to_bytes().unwrap();
+//! let text = String::from_utf8(bytes).unwrap();
+//! assert!(text.starts_with("Date: Tue, 1 Jan 2019 10:00:00 +0000\r\n"));
+//! assert!(text.contains("From: Jane Doe <jane@example.com>\r\nTo: john@example.com\r\n"));
+//! assert!(text.contains("Content-Type: text/calendar; method=REQUEST; charset=UTF-8\r\n"));
+//! ```
+use crate::ical::ICalendar;
+use crate::util::encode_base64;
+use std::borrow::Cow;
+use std::error;
+use std::fmt;
+
+/// An email message with a scheduling calendar.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Message<'a> {
+    /// Mailbox of the sender, e.g. `Jane Doe <jane@example.com>`
+    pub from: Cow<'a, str>,
+    /// Mailboxes of the recipients
+    pub to: Vec<Cow<'a, str>>,
+    /// Subject of the message
+    pub subject: Cow<'a, str>,
+    /// Origination date in the format of
+    /// [RFC5322](https://tools.ietf.org/html/rfc5322#section-3.3), e.g.
+    /// `Tue, 1 Jan 2019 10:00:00 +0000`
+    pub date: Cow<'a, str>,
+    /// Plain text alternative of the calendar
+    pub text: Cow<'a, str>,
+    /// Additional header fields like `Message-ID` or `Cc`. The header fields
+    /// written by the message itself are not allowed.
+    pub headers: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    /// Calendar with a `METHOD` property
+    pub calendar: ICalendar<'a>,
+}
+
+impl<'a> Message<'a> {
+    /// Creates a new message without recipients and an empty plain text part.
+    pub fn new<F, S, D>(from: F, subject: S, date: D, calendar: ICalendar<'a>) -> Self
+    where
+        F: Into<Cow<'a, str>>,
+        S: Into<Cow<'a, str>>,
+        D: Into<Cow<'a, str>>,
+    {
+        Message {
+            from: from.into(),
+            to: Vec::new(),
+            subject: subject.into(),
+            date: date.into(),
+            text: Cow::Borrowed(""),
+            headers: Vec::new(),
+            calendar,
+        }
+    }
+
+    /// Renders the message with its header fields and parts.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let method = self
+            .calendar
+            .0
+            .properties
+            .iter()
+            .find(|property| property.key.eq_ignore_ascii_case("METHOD"))
+            .map(|property| property.value.to_ascii_uppercase())
+            .ok_or(Error::MissingMethod)?;
+        if method.is_empty()
+            || !method
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        {
+            return Err(Error::InvalidHeader {
+                name: "Content-Type".to_string(),
+            });
+        }
+        if self.to.is_empty() {
+            return Err(Error::MissingRecipient);
+        }
+
+        if self.date.is_empty() || !self.date.is_ascii() {
+            return Err(invalid_header("Date"));
+        }
+
+        let mut output = String::new();
+        header(&mut output, "Date", &self.date)?;
+        header(&mut output, "From", &mailbox("From", &self.from)?)?;
+        let to: Result<Vec<_>, _> = self.to.iter().map(|to| mailbox("To", to)).collect();
+        header(&mut output, "To", &to?.join(",\r\n "))?;
+        header(
+            &mut output,
+            "Subject",
+            &encode_word("Subject", &self.subject)?,
+        )?;
+        for (name, value) in &self.headers {
+            let is_name =
+                !name.is_empty() && name.bytes().all(|b| b.is_ascii_graphic() && b != b':');
+            if !is_name || !value.is_ascii() {
+                return Err(invalid_header(name));
+            }
+            if RESERVED
+                .iter()
+                .any(|reserved| reserved.eq_ignore_ascii_case(name))
+            {
+                return Err(Error::DuplicateHeader {
+                    name: name.to_string(),
+                });
+            }
+            header(&mut output, name, value)?;
+        }
+
+        let text = self
+            .text
+            .replace("\r\n", "\n")
+            .replace('\r', "\n")
+            .replace('\n', "\r\n");
+        let calendar = self.calendar.to_string();
+        let boundary = boundary(&[&text, &calendar]);
+        header(&mut output, "MIME-Version", "1.0")?;
+        header(
+            &mut output,
+            "Content-Type",
+            &format!("multipart/alternative; boundary=\"{}\"", boundary),
+        )?;
+        output.push_str("\r\n");
+        part(&mut output, &boundary, "text/plain; charset=UTF-8", &text);
+        let content_type = format!("text/calendar; method={}; charset=UTF-8", method);
+        part(&mut output, &boundary, &content_type, &calendar);
+        output.push_str("--");
+        output.push_str(&boundary);
+        output.push_str("--\r\n");
+        Ok(output.into_bytes())
+    }
+}
+
+/// The error type for rendering messages.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// The calendar has no `METHOD` property.
+    MissingMethod,
+    /// The message has no recipient.
+    MissingRecipient,
+    /// A header field contains line breaks or characters that cannot be
+    /// encoded.
+    InvalidHeader {
+        /// Name of the header field
+        name: String,
+    },
+    /// An additional header field is written by the message itself, e.g.
+    /// `From` or `Content-Type`.
+    DuplicateHeader {
+        /// Name of the header field
+        name: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MissingMethod => write!(f, "calendar has no METHOD property"),
+            Error::MissingRecipient => write!(f, "message has no recipient"),
+            Error::InvalidHeader { name } => write!(f, "invalid {} header field", name),
+            Error::DuplicateHeader { name } => write!(f, "duplicate {} header field", name),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+// Header fields written by the message
+const RESERVED: [&str; 7] = [
+    "Content-Transfer-Encoding",
+    "Content-Type",
+    "Date",
+    "From",
+    "MIME-Version",
+    "Subject",
+    "To",
+];
+
+fn invalid_header(name: &str) -> Error {
+    Error::InvalidHeader {
+        name: name.to_string(),
+    }
+}
+
+fn header(output: &mut String, name: &str, value: &str) -> Result<(), Error> {
+    // Line breaks are only allowed as folding white space which is added by
+    // this module.
+    let is_folded = value
+        .split("\r\n")
+        .skip(1)
+        .all(|line| line.starts_with(' '));
+    if !is_folded
+        || value
+            .split("\r\n")
+            .any(|line| line.contains(&['\r', '\n'][..]))
+    {
+        return Err(invalid_header(name));
+    }
+    output.push_str(name);
+    output.push_str(": ");
+    output.push_str(value);
+    output.push_str("\r\n");
+    Ok(())
+}
+
+// Encodes the display name of a mailbox like `Name <address>` if necessary.
+// Addresses must be ASCII.
+fn mailbox<'v>(name: &str, value: &'v str) -> Result<Cow<'v, str>, Error> {
+    if value.is_ascii() {
+        return Ok(Cow::Borrowed(value));
+    }
+    match (value.rfind('<'), value.ends_with('>')) {
+        (Some(start), true) if value[start..].is_ascii() => {
+            let display_name = value[..start].trim().trim_matches('"');
+            Ok(Cow::Owned(format!(
+                "{} {}",
+                encode_word(name, display_name)?,
+                &value[start..]
+            )))
+        }
+        _ => Err(invalid_header(name)),
+    }
+}
+
+// Encodes non-ASCII text as several encoded words with the BASE64 encoding.
+// Every encoded word is at most 75 characters long.
+fn encode_word<'v>(name: &str, value: &'v str) -> Result<Cow<'v, str>, Error> {
+    if value.contains(&['\r', '\n'][..]) {
+        return Err(invalid_header(name));
+    }
+    if value.is_ascii() {
+        return Ok(Cow::Borrowed(value));
+    }
+    let mut words = Vec::new();
+    let mut rest = value;
+    while !rest.is_empty() {
+        // 39 bytes are 52 BASE64 characters, so that the first line with the
+        // field name stays within 76 characters.
+        let mut end = rest.len().min(39);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        words.push(format!(
+            "=?UTF-8?B?{}?=",
+            encode_base64(&rest.as_bytes()[..end])
+        ));
+        rest = &rest[end..];
+    }
+    Ok(Cow::Owned(words.join("\r\n ")))
+}
+
+// Writes a body part with 7bit encoding if possible, otherwise with BASE64
+// encoding in lines of 76 characters.
+fn part(output: &mut String, boundary: &str, content_type: &str, body: &str) {
+    output.push_str("--");
+    output.push_str(boundary);
+    output.push_str("\r\nContent-Type: ");
+    output.push_str(content_type);
+    if body.is_ascii() && body.split("\r\n").all(|line| line.len() <= 998) {
+        output.push_str("\r\nContent-Transfer-Encoding: 7bit\r\n\r\n");
+        output.push_str(body);
+    } else {
+        output.push_str("\r\nContent-Transfer-Encoding: base64\r\n\r\n");
+        let encoded = encode_base64(body.as_bytes());
+        for line in encoded.as_bytes().chunks(76) {
+            // BASE64 is ASCII
+            output.push_str(std::str::from_utf8(line).unwrap_or_default());
+            output.push_str("\r\n");
+        }
+    }
+    if !output.ends_with("\r\n") {
+        output.push_str("\r\n");
+    }
+    // The line break before the delimiter belongs to the delimiter.
+    output.push_str("\r\n");
+}
+
+// Creates a boundary that does not occur in the bodies.
+fn boundary(bodies: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for body in bodies {
+        for &b in body.as_bytes() {
+            hash = (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    loop {
+        let boundary = format!("=_ics_{:016x}", hash);
+        if !bodies.iter().any(|body| body.contains(&boundary)) {
+            return boundary;
+        }
+        hash = hash.wrapping_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Message};
+    use crate::properties::{Method, Summary};
+    use crate::{Event, ICalendar};
+
+    const DATE: &str = "Tue, 1 Jan 2019 10:00:00 +0000";
+
+    fn calendar(summary: &str) -> ICalendar<'_> {
+        let mut event = Event::new("uid-1", "20190101T100000Z");
+        event.push(Summary::new(summary));
+        let mut calendar = ICalendar::new("2.0", "-//Test//EN");
+        calendar.push(Method::new("REQUEST"));
+        calendar.add_event(event);
+        calendar
+    }
+
+    #[test]
+    fn message() {
+        let mut message = Message::new("jane@example.com", "Invitation", DATE, calendar("Meeting"));
+        message.to.push("john@example.com".into());
+        message.to.push("jim@example.com".into());
+        message.text = "Hello\nworld\rand\r\nyou".into();
+        let text = String::from_utf8(message.to_bytes().unwrap()).unwrap();
+        let boundary = "=_ics_";
+        let start = text.find(boundary).unwrap();
+        let boundary = &text[start..start + 22];
+        assert_eq!(
+            text,
+            format!(
+                concat!(
+                    "Date: Tue, 1 Jan 2019 10:00:00 +0000\r\n",
+                    "From: jane@example.com\r\n",
+                    "To: john@example.com,\r\n jim@example.com\r\n",
+                    "Subject: Invitation\r\n",
+                    "MIME-Version: 1.0\r\n",
+                    "Content-Type: multipart/alternative; boundary=\"{0}\"\r\n",
+                    "\r\n",
+                    "--{0}\r\n",
+                    "Content-Type: text/plain; charset=UTF-8\r\n",
+                    "Content-Transfer-Encoding: 7bit\r\n",
+                    "\r\n",
+                    "Hello\r\nworld\r\nand\r\nyou\r\n",
+                    "\r\n",
+                    "--{0}\r\n",
+                    "Content-Type: text/calendar; method=REQUEST; charset=UTF-8\r\n",
+                    "Content-Transfer-Encoding: 7bit\r\n",
+                    "\r\n",
+                    "{1}",
+                    "\r\n",
+                    "--{0}--\r\n"
+                ),
+                boundary,
+                calendar("Meeting")
+            )
+        );
+    }
+
+    #[test]
+    fn encoding() {
+        let mut message = Message::new(
+            "Jürgen <juergen@example.com>",
+            "Einladung: Besprechung über das Budget für das nächste Quartal",
+            DATE,
+            calendar("Besprechung über das Budget"),
+        );
+        message.to.push("john@example.com".into());
+        let text = String::from_utf8(message.to_bytes().unwrap()).unwrap();
+        assert!(text.contains("\r\nFrom: =?UTF-8?B?SsO8cmdlbg==?= <juergen@example.com>\r\n"));
+        assert!(text.contains(concat!(
+            "Subject: =?UTF-8?B?RWlubGFkdW5nOiBCZXNwcmVjaHVuZyDDvGJlciBkYXMgQnVkZ2V0?=\r\n",
+            " =?UTF-8?B?IGbDvHIgZGFzIG7DpGNoc3RlIFF1YXJ0YWw=?=\r\n"
+        )));
+        assert!(text.contains("Content-Transfer-Encoding: base64\r\n"));
+        assert!(text.lines().all(|line| line.len() <= 78));
+    }
+
+    fn invitation() -> Message<'static> {
+        let mut message = Message::new("jane@example.com", "Invitation", DATE, calendar("Meeting"));
+        message.to.push("john@example.com".into());
+        message
+    }
+
+    #[test]
+    fn missing_recipient() {
+        let mut message = invitation();
+        message.to.clear();
+        assert_eq!(message.to_bytes(), Err(Error::MissingRecipient));
+    }
+
+    #[test]
+    fn header_injection() {
+        let mut message = invitation();
+        message.subject = "Invitation\r\nBcc: eve@example.com".into();
+        assert_eq!(
+            message.to_bytes(),
+            Err(Error::InvalidHeader {
+                name: "Subject".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn missing_date() {
+        let mut message = invitation();
+        message.date = "".into();
+        assert_eq!(
+            message.to_bytes(),
+            Err(Error::InvalidHeader {
+                name: "Date".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn duplicate_header() {
+        let mut message = invitation();
+        message
+            .headers
+            .push(("content-type".into(), "text/plain".into()));
+        assert_eq!(
+            message.to_bytes(),
+            Err(Error::DuplicateHeader {
+                name: "content-type".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn missing_method() {
+        let mut message = invitation();
+        message.calendar = ICalendar::new("2.0", "-//Test//EN");
+        assert_eq!(message.to_bytes(), Err(Error::MissingMethod));
+    }
+}
@@ -0,0 +1,1084 @@
+//! Scheduling messages as specified in [RFC5546](https://tools.ietf.org/html/rfc5546) (iTIP).
+//!
+//! Every iTIP method requires a different set of properties in the scheduled
+//! `VEVENT` or `VTODO`. The functions of this module take the organizer's or
+//! attendee's copy of a component, check that the required properties exist,
+//! remove properties the method does not allow and return a calendar with the
+//! matching `METHOD` property. Received replies, cancellations and counter
+//! proposals are applied to the stored components with [`process`].
+//!
+//! # Example
+//! ```
+//! use ics::itip;
+//! use ics::parameters::PartStat;
+//! use ics::properties::{Attendee, DtStart, Organizer, Summary};
+//! use ics::Event;
+//!
+//! let mut event = Event::new("b68378cf-872d-44f1-9703-5e3725c56e71", "19970611T190000Z");
+//! event.push(DtStart::new("19970701T200000Z"));
+//! event.push(Summary::new("Conference"));
+//! event.push(Organizer::new("mailto:a@example.com"));
+//! event.push(Attendee::new("mailto:b@example.com"));
+//! event.push(Attendee::new("mailto:c@example.com"));
+//!
+//! let request = itip::request("-//Example//EN", event.clone()).unwrap();
+//! assert!(request.to_string().contains("METHOD:REQUEST\r\n"));
+//!
+//! // The reply only contains the replying attendee.
+//! let reply = itip::reply(
+//!     "-//Example//EN",
+//!     event,
+//!     "mailto:b@example.com",
+//!     PartStat::ACCEPTED,
+//!     "19970612T080000Z",
+//! )
+//! .unwrap();
+//! assert_eq!(
+//!     reply.to_string(),
+//!     "BEGIN:VCALENDAR\r\n\
+//!      VERSION:2.0\r\n\
+//!      PRODID:-//Example//EN\r\n\
+//!      METHOD:REPLY\r\n\
+//!      BEGIN:VEVENT\r\n\
+//!      UID:b68378cf-872d-44f1-9703-5e3725c56e71\r\n\
+//!      DTSTAMP:19970612T080000Z\r\n\
+//!      ORGANIZER:mailto:a@example.com\r\n\
+//!      ATTENDEE;PARTSTAT=ACCEPTED:mailto:b@example.com\r\n\
+//!      END:VEVENT\r\n\
+//!      END:VCALENDAR\r\n"
+//! );
+//! ```
+use crate::components::{find_parameter, Component, Parameter, Property};
+use crate::ical::ICalendar;
+use crate::parameters::PartStat;
+use crate::properties::{Attendee, DtStamp, Method, Status};
+use std::borrow::Cow;
+use std::error;
+use std::fmt;
+
+/// The error type for creating scheduling messages.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// Only `VEVENT` and `VTODO` components can be scheduled.
+    UnsupportedComponent {
+        /// Name of the component
+        name: String,
+    },
+    /// A property required by the method is missing.
+    MissingProperty {
+        /// Name of the method, e.g. `REQUEST`
+        method: &'static str,
+        /// Name of the missing property
+        property: &'static str,
+    },
+    /// The calendar user is not an attendee of the component.
+    UnknownAttendee {
+        /// Calendar user address of the attendee
+        address: String,
+    },
+    /// The `METHOD` of the message is missing or cannot be processed.
+    UnsupportedMethod {
+        /// Value of the `METHOD` property
+        method: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnsupportedComponent { name } => {
+                write!(f, "{} components cannot be scheduled", name)
+            }
+            Error::MissingProperty { method, property } => {
+                write!(f, "{} requires the {} property", method, property)
+            }
+            Error::UnknownAttendee { address } => write!(f, "{} is not an attendee", address),
+            Error::UnsupportedMethod { method } => {
+                write!(f, "method {:?} cannot be processed", method)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Creates a `PUBLISH` message which posts the component without expecting
+/// replies. `ATTENDEE` properties are removed.
+///
+/// Requires `DTSTAMP`, `DTSTART`, `ORGANIZER`, `SUMMARY`, `UID` and for to-dos
+/// `PRIORITY`.
+pub fn publish<'a, P, C>(prodid: P, component: C) -> Result<ICalendar<'a>, Error>
+where
+    P: Into<Cow<'a, str>>,
+    C: Into<Component<'a>>,
+{
+    let mut component = scheduled(component.into())?;
+    require(
+        &component,
+        "PUBLISH",
+        &["DTSTAMP", "ORGANIZER", "SUMMARY", "UID"],
+    )?;
+    require_scheduling(&component, "PUBLISH")?;
+    component
+        .properties
+        .retain(|property| !is(property, "ATTENDEE"));
+    Ok(message(prodid, "PUBLISH", component))
+}
+
+/// Creates a `REQUEST` message which invites the attendees or updates an
+/// existing component.
+///
+/// Requires at least one `ATTENDEE` as well as `DTSTAMP`, `DTSTART`,
+/// `ORGANIZER`, `SUMMARY`, `UID` and for to-dos `PRIORITY`.
+pub fn request<'a, P, C>(prodid: P, component: C) -> Result<ICalendar<'a>, Error>
+where
+    P: Into<Cow<'a, str>>,
+    C: Into<Component<'a>>,
+{
+    let component = scheduled(component.into())?;
+    require(
+        &component,
+        "REQUEST",
+        &["ATTENDEE", "DTSTAMP", "ORGANIZER", "SUMMARY", "UID"],
+    )?;
+    require_scheduling(&component, "REQUEST")?;
+    Ok(message(prodid, "REQUEST", component))
+}
+
+/// Creates an `ADD` message which adds instances to a recurring component.
+///
+/// Requires the same properties as [`request`] and `SEQUENCE`.
+pub fn add<'a, P, C>(prodid: P, component: C) -> Result<ICalendar<'a>, Error>
+where
+    P: Into<Cow<'a, str>>,
+    C: Into<Component<'a>>,
+{
+    let component = scheduled(component.into())?;
+    require(
+        &component,
+        "ADD",
+        &[
+            "ATTENDEE",
+            "DTSTAMP",
+            "ORGANIZER",
+            "SEQUENCE",
+            "SUMMARY",
+            "UID",
+        ],
+    )?;
+    require_scheduling(&component, "ADD")?;
+    Ok(message(prodid, "ADD", component))
+}
+
+/// Creates a `CANCEL` message which cancels the component for all attendees.
+/// The `STATUS` is set to `CANCELLED` and properties that do not identify the
+/// component are removed.
+///
+/// Requires `DTSTAMP`, `ORGANIZER`, `SEQUENCE` and `UID`. The `SEQUENCE`
+/// should be incremented before.
+pub fn cancel<'a, P, C>(prodid: P, component: C) -> Result<ICalendar<'a>, Error>
+where
+    P: Into<Cow<'a, str>>,
+    C: Into<Component<'a>>,
+{
+    let mut component = cancelled(component.into())?;
+    component.add_property(Status::cancelled());
+    Ok(message(prodid, "CANCEL", component))
+}
+
+/// Creates a `CANCEL` message which removes the given attendees from the
+/// component. Other attendees are not part of the message.
+///
+/// Requires `DTSTAMP`, `ORGANIZER`, `SEQUENCE` and `UID`.
+pub fn cancel_attendees<'a, P, C>(
+    prodid: P,
+    component: C,
+    attendees: &[&str],
+) -> Result<ICalendar<'a>, Error>
+where
+    P: Into<Cow<'a, str>>,
+    C: Into<Component<'a>>,
+{
+    let mut component = cancelled(component.into())?;
+    for address in attendees {
+        if find_attendee(&component, address).is_none() {
+            return Err(Error::UnknownAttendee {
+                address: address.to_string(),
+            });
+        }
+    }
+    component.properties.retain(|property| {
+        !is(property, "ATTENDEE")
+            || attendees
+                .iter()
+                .any(|address| same_address(&property.value, address))
+    });
+    Ok(message(prodid, "CANCEL", component))
+}
+
+/// Creates a `REPLY` message of an attendee with the new participation status.
+/// The reply only contains the replying attendee and the properties that
+/// identify the component.
+///
+/// Requires `ORGANIZER` and `UID`. The `DTSTAMP` is the time of the reply.
+pub fn reply<'a, P, C, D>(
+    prodid: P,
+    component: C,
+    attendee: &str,
+    status: PartStat<'a>,
+    dtstamp: D,
+) -> Result<ICalendar<'a>, Error>
+where
+    P: Into<Cow<'a, str>>,
+    C: Into<Component<'a>>,
+    D: Into<Cow<'a, str>>,
+{
+    let mut component = scheduled(component.into())?;
+    require(&component, "REPLY", &["ORGANIZER", "UID"])?;
+    let mut property = find_attendee(&component, attendee)
+        .cloned()
+        .ok_or_else(|| Error::UnknownAttendee {
+            address: attendee.to_string(),
+        })?;
+    remove_parameter(&mut property, "RSVP");
+    remove_parameter(&mut property, "PARTSTAT");
+    property.add(status);
+    identify(&mut component, Some(DtStamp::new(dtstamp).into()), &[]);
+    component.add_property(property);
+    Ok(message(prodid, "REPLY", component))
+}
+
+/// Creates a `REFRESH` message with which an attendee requests the latest
+/// version of the component.
+///
+/// Requires `ORGANIZER` and `UID`. The `DTSTAMP` is the time of the request.
+pub fn refresh<'a, P, C, D>(
+    prodid: P,
+    component: C,
+    attendee: &str,
+    dtstamp: D,
+) -> Result<ICalendar<'a>, Error>
+where
+    P: Into<Cow<'a, str>>,
+    C: Into<Component<'a>>,
+    D: Into<Cow<'a, str>>,
+{
+    let mut component = scheduled(component.into())?;
+    require(&component, "REFRESH", &["ORGANIZER", "UID"])?;
+    let property = match find_attendee(&component, attendee) {
+        Some(property) => Property::new("ATTENDEE", property.value.clone()),
+        None => Attendee::new(attendee.to_string()).into(),
+    };
+    identify(&mut component, Some(DtStamp::new(dtstamp).into()), &[]);
+    component
+        .properties
+        .retain(|property| !is(property, "SEQUENCE"));
+    component.add_property(property);
+    Ok(message(prodid, "REFRESH", component))
+}
+
+/// Creates a `COUNTER` message with which an attendee proposes changes to the
+/// component, e.g. another `DTSTART`.
+///
+/// Requires `DTSTAMP`, `DTSTART`, `ORGANIZER`, `SUMMARY`, `UID` and for to-dos
+/// `PRIORITY`.
+pub fn counter<'a, P, C>(prodid: P, component: C) -> Result<ICalendar<'a>, Error>
+where
+    P: Into<Cow<'a, str>>,
+    C: Into<Component<'a>>,
+{
+    let component = scheduled(component.into())?;
+    require(
+        &component,
+        "COUNTER",
+        &["DTSTAMP", "ORGANIZER", "SUMMARY", "UID"],
+    )?;
+    require_scheduling(&component, "COUNTER")?;
+    Ok(message(prodid, "COUNTER", component))
+}
+
+/// Creates a `DECLINECOUNTER` message with which the organizer rejects the
+/// received `COUNTER` component.
+///
+/// Requires `ATTENDEE`, `DTSTAMP`, `ORGANIZER` and `UID`.
+pub fn decline_counter<'a, P, C>(prodid: P, component: C) -> Result<ICalendar<'a>, Error>
+where
+    P: Into<Cow<'a, str>>,
+    C: Into<Component<'a>>,
+{
+    let mut component = scheduled(component.into())?;
+    require(
+        &component,
+        "DECLINECOUNTER",
+        &["ATTENDEE", "DTSTAMP", "ORGANIZER", "UID"],
+    )?;
+    identify(
+        &mut component,
+        None,
+        &["ATTENDEE", "COMMENT", "REQUEST-STATUS"],
+    );
+    Ok(message(prodid, "DECLINECOUNTER", component))
+}
+
+// Properties that identify the scheduled component or instance
+const IDENTIFYING: [&str; 4] = ["ORGANIZER", "RECURRENCE-ID", "SEQUENCE", "UID"];
+
+// Properties which PUBLISH, REQUEST, ADD and COUNTER additionally require
+// depending on the component
+const EVENT_SCHEDULING: [&str; 1] = ["DTSTART"];
+const TODO_SCHEDULING: [&str; 2] = ["DTSTART", "PRIORITY"];
+
+fn is(property: &Property, name: &str) -> bool {
+    property.key.eq_ignore_ascii_case(name)
+}
+
+// Also removes the bookkeeping of `process` from the attendees.
+fn scheduled(mut component: Component) -> Result<Component, Error> {
+    if component.name == "VEVENT" || component.name == "VTODO" {
+        for property in &mut component.properties {
+            if is(property, "ATTENDEE") {
+                remove_parameter(property, "X-DTSTAMP");
+            }
+        }
+        Ok(component)
+    } else {
+        Err(Error::UnsupportedComponent {
+            name: component.name.into_owned(),
+        })
+    }
+}
+
+// Parameter names are case-insensitive.
+fn remove_parameter(property: &mut Property, key: &str) {
+    let names: Vec<_> = property
+        .parameters
+        .keys()
+        .filter(|name| name.eq_ignore_ascii_case(key))
+        .cloned()
+        .collect();
+    for name in names {
+        property.parameters.remove(&name);
+    }
+}
+
+fn require(
+    component: &Component,
+    method: &'static str,
+    names: &[&'static str],
+) -> Result<(), Error> {
+    for &name in names {
+        if !component
+            .properties
+            .iter()
+            .any(|property| is(property, name))
+        {
+            return Err(Error::MissingProperty {
+                method,
+                property: name,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn require_scheduling(component: &Component, method: &'static str) -> Result<(), Error> {
+    if component.name == "VTODO" {
+        require(component, method, &TODO_SCHEDULING)
+    } else {
+        require(component, method, &EVENT_SCHEDULING)
+    }
+}
+
+// Keeps the identifying properties, DTSTAMP (unless it is replaced) and the
+// given properties as well as non-standard properties and removes the
+// sub-components.
+fn identify<'a>(component: &mut Component<'a>, mut dtstamp: Option<Property<'a>>, names: &[&str]) {
+    component.subcomponents.clear();
+    let properties = std::mem::replace(&mut component.properties, Vec::new());
+    for property in properties {
+        if is(&property, "DTSTAMP") {
+            component
+                .properties
+                .push(dtstamp.take().unwrap_or(property));
+        } else if IDENTIFYING
+            .iter()
+            .chain(names)
+            .any(|name| is(&property, name))
+            || property.key.starts_with("X-")
+        {
+            component.properties.push(property);
+        }
+    }
+    component.properties.extend(dtstamp);
+}
+
+fn cancelled(component: Component) -> Result<Component, Error> {
+    let mut component = scheduled(component)?;
+    require(
+        &component,
+        "CANCEL",
+        &["DTSTAMP", "ORGANIZER", "SEQUENCE", "UID"],
+    )?;
+    identify(&mut component, None, &["ATTENDEE", "COMMENT"]);
+    Ok(component)
+}
+
+fn find_attendee<'c, 'a>(component: &'c Component<'a>, address: &str) -> Option<&'c Property<'a>> {
+    component
+        .properties
+        .iter()
+        .find(|property| is(property, "ATTENDEE") && same_address(&property.value, address))
+}
+
+// Calendar user addresses are compared case-insensitively because the scheme
+// and domain are case-insensitive and mail servers usually treat the local
+// part alike.
+fn same_address(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+fn message<'a, P>(prodid: P, method: &'static str, component: Component<'a>) -> ICalendar<'a>
+where
+    P: Into<Cow<'a, str>>,
+{
+    let mut calendar = ICalendar::new("2.0", prodid);
+    calendar.push(Method::new(method));
+    calendar.add_component(component);
+    calendar
+}
+
+/// The result of applying one component of a scheduling message with
+/// [`process`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report<'a> {
+    /// `UID` of the component
+    pub uid: String,
+    /// `RECURRENCE-ID` of the component if the message refers to an instance
+    pub recurrence_id: Option<String>,
+    /// What happened to the stored component
+    pub outcome: Outcome<'a>,
+}
+
+/// How a component of a scheduling message was processed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<'a> {
+    /// The stored component was updated.
+    Applied,
+    /// The message was ignored because it is older than the stored component,
+    /// i.e. it has a lower `SEQUENCE` or the same `SEQUENCE` and an earlier
+    /// `DTSTAMP`.
+    Stale,
+    /// The message was ignored because it refers to a newer `SEQUENCE` than
+    /// the stored component.
+    Conflict {
+        /// `SEQUENCE` of the stored component
+        stored: u32,
+        /// `SEQUENCE` of the message
+        received: u32,
+    },
+    /// No component with the `UID` and `RECURRENCE-ID` is stored.
+    NotFound,
+    /// The replying calendar user is not an attendee of the stored component.
+    UnknownAttendee(String),
+    /// The component lacks a property the method requires. The stored
+    /// components are not modified.
+    Invalid(Error),
+    /// The attendee proposes changes with `COUNTER`. The stored component is
+    /// not modified. The organizer accepts them with a new `REQUEST` or
+    /// rejects them with [`decline_counter`].
+    Counter(Vec<Property<'a>>),
+}
+
+/// Applies a `REPLY`, `CANCEL` or `COUNTER` message to the stored components
+/// of a calendar.
+///
+/// Every `VEVENT` and `VTODO` of the message is matched by `UID` and
+/// `RECURRENCE-ID` with a stored component and its `SEQUENCE` and `DTSTAMP`
+/// are compared before it is applied:
+/// - `REPLY` updates the `PARTSTAT` of the replying attendee. The `DTSTAMP`
+///   of the reply is kept in the `X-DTSTAMP` parameter of the attendee, so a
+///   delayed reply does not override a later one. The message builders of
+///   this module remove the parameter again.
+/// - `CANCEL` sets the `STATUS` to `CANCELLED` or removes the cancelled
+///   attendees and takes over the `SEQUENCE` and `DTSTAMP`. If only an
+///   instance of a recurring component is cancelled that is not stored
+///   separately, its `RECURRENCE-ID` is added as `EXDATE`.
+/// - `COUNTER` reports the proposed changes.
+///
+/// Only an unsupported `METHOD` is returned as error. Components which cannot
+/// be applied are reported with their [`Outcome`] and the others are applied
+/// nevertheless.
+///
+/// # Example
+/// ```
+/// use ics::itip::{self, Outcome};
+/// use ics::parameters::PartStat;
+/// use ics::properties::{Attendee, DtStart, Organizer, Summary};
+/// use ics::{Event, ICalendar};
+///
+/// let mut event = Event::new("uid-1", "20190101T100000Z");
+/// event.push(DtStart::new("20190201T100000Z"));
+/// event.push(Summary::new("Meeting"));
+/// event.push(Organizer::new("mailto:a@example.com"));
+/// event.push(Attendee::new("mailto:b@example.com"));
+///
+/// let reply = itip::reply(
+///     "-//Example//EN",
+///     event.clone(),
+///     "mailto:b@example.com",
+///     PartStat::ACCEPTED,
+///     "20190102T100000Z",
+/// )
+/// .unwrap();
+///
+/// let mut store = ICalendar::new("2.0", "-//Example//EN");
+/// store.add_event(event);
+/// let reports = itip::process(&mut store, &reply).unwrap();
+/// assert_eq!(reports[0].outcome, Outcome::Applied);
+/// assert!(store.to_string().contains(
+///     "ATTENDEE;PARTSTAT=ACCEPTED;X-DTSTAMP=20190102T100000Z:mailto:b@example.com\r\n"
+/// ));
+/// ```
+pub fn process<'a>(
+    store: &mut ICalendar<'a>,
+    message: &ICalendar<'a>,
+) -> Result<Vec<Report<'a>>, Error> {
+    let method = find(&message.0, "METHOD").map_or("", |property| &property.value);
+    let method = match method.to_ascii_uppercase().as_str() {
+        "REPLY" => "REPLY",
+        "CANCEL" => "CANCEL",
+        "COUNTER" => "COUNTER",
+        _ => {
+            return Err(Error::UnsupportedMethod {
+                method: method.to_string(),
+            })
+        }
+    };
+
+    let mut reports = Vec::new();
+    for received in &message.0.subcomponents {
+        if received.name != "VEVENT" && received.name != "VTODO" {
+            continue;
+        }
+        let uid = find(received, "UID").map_or("", |property| &property.value);
+        let recurrence_id = find(received, "RECURRENCE-ID").map(|property| &property.value);
+        let outcome = if let Err(error) = require(received, method, &["UID"]) {
+            Outcome::Invalid(error)
+        } else {
+            match find_stored(store, &received.name, uid, recurrence_id.map(|id| &**id)) {
+                Some(stored) => apply(method, stored, received).unwrap_or_else(Outcome::Invalid),
+                None if method == "CANCEL" && recurrence_id.is_some() => {
+                    match find_stored(store, &received.name, uid, None) {
+                        Some(master) => exclude(master, received),
+                        None => Outcome::NotFound,
+                    }
+                }
+                None => Outcome::NotFound,
+            }
+        };
+        reports.push(Report {
+            uid: uid.to_string(),
+            recurrence_id: recurrence_id.map(|id| id.to_string()),
+            outcome,
+        });
+    }
+    Ok(reports)
+}
+
+fn apply<'a>(
+    method: &'static str,
+    stored: &mut Component<'a>,
+    received: &Component<'a>,
+) -> Result<Outcome<'a>, Error> {
+    // Replies are compared with the previous reply of the attendee instead.
+    let (stored_sequence, received_sequence) = (sequence(stored), sequence(received));
+    if received_sequence < stored_sequence || method != "REPLY" && is_older(received, stored) {
+        return Ok(Outcome::Stale);
+    }
+    if received_sequence > stored_sequence && method != "CANCEL" {
+        return Ok(Outcome::Conflict {
+            stored: stored_sequence,
+            received: received_sequence,
+        });
+    }
+
+    match method {
+        "REPLY" => {
+            let reply = find(received, "ATTENDEE").ok_or(Error::MissingProperty {
+                method,
+                property: "ATTENDEE",
+            })?;
+            let attendee = stored.properties.iter_mut().find(|property| {
+                is(property, "ATTENDEE") && same_address(&property.value, &reply.value)
+            });
+            let attendee = match attendee {
+                Some(attendee) => attendee,
+                None => return Ok(Outcome::UnknownAttendee(reply.value.to_string())),
+            };
+            let dtstamp = find(received, "DTSTAMP").map(|property| &property.value);
+            let replied = find_parameter(&attendee.parameters, "X-DTSTAMP");
+            if received_sequence == stored_sequence && is_earlier(dtstamp, replied) {
+                return Ok(Outcome::Stale);
+            }
+            let status = find_parameter(&reply.parameters, "PARTSTAT")
+                .cloned()
+                .unwrap_or(Cow::Borrowed("NEEDS-ACTION"));
+            for key in &["RSVP", "PARTSTAT", "X-DTSTAMP"] {
+                remove_parameter(attendee, key);
+            }
+            attendee.add(PartStat::new(status));
+            if let Some(dtstamp) = dtstamp {
+                attendee.add(Parameter::new("X-DTSTAMP", dtstamp.clone()));
+            }
+            Ok(Outcome::Applied)
+        }
+        "CANCEL" => {
+            if find(received, "STATUS").map_or(false, |status| status.value == "CANCELLED") {
+                replace(stored, Status::cancelled().into());
+            } else {
+                stored.properties.retain(|property| {
+                    !is(property, "ATTENDEE")
+                        || !received.properties.iter().any(|cancelled| {
+                            is(cancelled, "ATTENDEE")
+                                && same_address(&cancelled.value, &property.value)
+                        })
+                });
+            }
+            for name in &["SEQUENCE", "DTSTAMP"] {
+                if let Some(property) = find(received, name) {
+                    replace(stored, property.clone());
+                }
+            }
+            Ok(Outcome::Applied)
+        }
+        _ => {
+            let changes = received
+                .properties
+                .iter()
+                .filter(|property| {
+                    !["ATTENDEE", "DTSTAMP", "ORGANIZER", "SEQUENCE", "UID"]
+                        .iter()
+                        .any(|name| is(property, name))
+                        && !stored.properties.contains(property)
+                })
+                .cloned()
+                .collect();
+            Ok(Outcome::Counter(changes))
+        }
+    }
+}
+
+// Cancels an instance of a recurring component by excluding its date.
+fn exclude<'a>(master: &mut Component<'a>, received: &Component<'a>) -> Outcome<'a> {
+    if sequence(received) < sequence(master) || is_older(received, master) {
+        return Outcome::Stale;
+    }
+    if let Some(recurrence_id) = find(received, "RECURRENCE-ID") {
+        let mut exdate = Property::new("EXDATE", recurrence_id.value.clone());
+        exdate.parameters = recurrence_id.parameters.clone();
+        remove_parameter(&mut exdate, "RANGE");
+        master.add_property(exdate);
+    }
+    Outcome::Applied
+}
+
+fn find<'c, 'a>(component: &'c Component<'a>, name: &str) -> Option<&'c Property<'a>> {
+    component
+        .properties
+        .iter()
+        .find(|property| is(property, name))
+}
+
+fn find_stored<'c, 'a>(
+    store: &'c mut ICalendar<'a>,
+    name: &str,
+    uid: &str,
+    recurrence_id: Option<&str>,
+) -> Option<&'c mut Component<'a>> {
+    store.0.subcomponents.iter_mut().find(|component| {
+        component.name == name
+            && find(component, "UID").map_or(false, |property| property.value == uid)
+            && find(component, "RECURRENCE-ID").map(|property| &*property.value) == recurrence_id
+    })
+}
+
+// Replaces the first property with the same name or adds it.
+fn replace<'a>(component: &mut Component<'a>, property: Property<'a>) {
+    match component
+        .properties
+        .iter_mut()
+        .find(|existing| existing.key == property.key)
+    {
+        Some(existing) => *existing = property,
+        None => component.properties.push(property),
+    }
+}
+
+// A missing or invalid SEQUENCE is 0.
+fn sequence(component: &Component) -> u32 {
+    find(component, "SEQUENCE")
+        .and_then(|property| property.value.parse().ok())
+        .unwrap_or(0)
+}
+
+// Compares the DTSTAMP of components with the same SEQUENCE.
+fn is_older(received: &Component, stored: &Component) -> bool {
+    sequence(received) == sequence(stored)
+        && is_earlier(
+            find(received, "DTSTAMP").map(|property| &property.value),
+            find(stored, "DTSTAMP").map(|property| &property.value),
+        )
+}
+
+// UTC date-times have a fixed length and can be compared as strings.
+fn is_earlier(received: Option<&Cow<str>>, stored: Option<&Cow<str>>) -> bool {
+    let is_utc = |value: &str| value.len() == 16 && value.ends_with('Z');
+    match (received, stored) {
+        (Some(received), Some(stored)) if is_utc(received) && is_utc(stored) => received < stored,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameters::RSVP;
+    use crate::properties::{
+        Comment, DtStart, Location, Organizer, Priority, RecurrenceID, Sequence, Summary, Trigger,
+    };
+    use crate::{Alarm, Event, Journal, ToDo};
+
+    fn event() -> Event<'static> {
+        let mut event = Event::new("uid-1", "20190101T100000Z");
+        event.push(DtStart::new("20190201T100000Z"));
+        event.push(Summary::new("Meeting"));
+        event.push(Organizer::new("mailto:a@example.com"));
+        let mut attendee = Attendee::new("mailto:b@example.com");
+        attendee.add(RSVP::True);
+        event.push(attendee);
+        event.push(Attendee::new("mailto:c@example.com"));
+        event.push(Comment::new("Bring slides"));
+        event.push(Sequence::new("1"));
+        event.add_alarm(Alarm::audio(Trigger::new("-PT15M")));
+        event
+    }
+
+    #[test]
+    fn organizer_messages() {
+        let publish = publish("-//Test//EN", event()).unwrap().to_string();
+        assert!(publish.contains("METHOD:PUBLISH\r\n"));
+        assert!(!publish.contains("ATTENDEE"));
+
+        let request = request("-//Test//EN", event()).unwrap().to_string();
+        assert!(request.contains("METHOD:REQUEST\r\n"));
+        assert!(request.contains("BEGIN:VALARM\r\n"));
+        assert!(add("-//Test//EN", event()).is_ok());
+
+        let cancel = cancel("-//Test//EN", event()).unwrap().to_string();
+        assert!(cancel.contains("STATUS:CANCELLED\r\n"));
+        assert!(cancel.contains("ATTENDEE:mailto:c@example.com\r\n"));
+        assert!(!cancel.contains("SUMMARY") && !cancel.contains("VALARM"));
+
+        let cancel = cancel_attendees("-//Test//EN", event(), &["MAILTO:c@example.com"])
+            .unwrap()
+            .to_string();
+        assert!(!cancel.contains("STATUS") && !cancel.contains("mailto:b@example.com"));
+        assert!(cancel.contains("ATTENDEE:mailto:c@example.com\r\n"));
+    }
+
+    #[test]
+    fn attendee_messages() {
+        let reply = reply(
+            "-//Test//EN",
+            event(),
+            "mailto:b@example.com",
+            PartStat::DECLINED,
+            "20190102T100000Z",
+        )
+        .unwrap()
+        .to_string();
+        assert!(reply.contains(concat!(
+            "BEGIN:VEVENT\r\n",
+            "UID:uid-1\r\n",
+            "DTSTAMP:20190102T100000Z\r\n",
+            "ORGANIZER:mailto:a@example.com\r\n",
+            "SEQUENCE:1\r\n",
+            "ATTENDEE;PARTSTAT=DECLINED:mailto:b@example.com\r\n",
+            "END:VEVENT\r\n"
+        )));
+
+        let refresh = refresh(
+            "-//Test//EN",
+            event(),
+            "mailto:c@example.com",
+            "20190102T100000Z",
+        )
+        .unwrap()
+        .to_string();
+        assert!(refresh.contains("METHOD:REFRESH\r\n"));
+        assert!(!refresh.contains("SEQUENCE"));
+
+        let counter = counter("-//Test//EN", event()).unwrap();
+        let decline = decline_counter("-//Test//EN", event()).unwrap().to_string();
+        assert!(counter.to_string().contains("METHOD:COUNTER\r\n"));
+        assert!(decline.contains("METHOD:DECLINECOUNTER\r\n"));
+        assert!(decline.contains("COMMENT:Bring slides\r\n"));
+        assert!(!decline.contains("DTSTART"));
+    }
+
+    #[test]
+    fn missing_attendee() {
+        assert_eq!(
+            request("-//Test//EN", Event::new("uid-1", "20190101T100000Z")),
+            Err(Error::MissingProperty {
+                method: "REQUEST",
+                property: "ATTENDEE"
+            })
+        );
+        let mut unattended = event();
+        unattended
+            .properties
+            .retain(|property| property.key != "ATTENDEE");
+        assert_eq!(
+            decline_counter("-//Test//EN", unattended),
+            Err(Error::MissingProperty {
+                method: "DECLINECOUNTER",
+                property: "ATTENDEE"
+            })
+        );
+    }
+
+    #[test]
+    fn todo_requirements() {
+        let mut todo = ToDo::new("uid-1", "20190101T100000Z");
+        todo.push(Summary::new("Task"));
+        todo.push(Organizer::new("mailto:a@example.com"));
+        todo.push(DtStart::new("20190201T100000Z"));
+        assert_eq!(
+            publish("-//Test//EN", todo.clone()),
+            Err(Error::MissingProperty {
+                method: "PUBLISH",
+                property: "PRIORITY"
+            })
+        );
+        todo.push(Priority::new("1"));
+        assert!(publish("-//Test//EN", todo.clone()).is_ok());
+        assert!(cancel("-//Test//EN", todo).is_err());
+    }
+
+    #[test]
+    fn unknown_attendee() {
+        assert_eq!(
+            reply(
+                "-//Test//EN",
+                event(),
+                "mailto:d@example.com",
+                PartStat::ACCEPTED,
+                "20190102T100000Z"
+            ),
+            Err(Error::UnknownAttendee {
+                address: "mailto:d@example.com".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn unsupported_component() {
+        assert_eq!(
+            publish("-//Test//EN", Journal::new("uid-1", "20190101T100000Z")),
+            Err(Error::UnsupportedComponent {
+                name: "VJOURNAL".to_string()
+            })
+        );
+    }
+
+    fn reply_from(
+        address: &'static str,
+        sequence: &'static str,
+        dtstamp: &'static str,
+    ) -> ICalendar<'static> {
+        let mut event = Event::new("uid-1", "20190101T100000Z");
+        event.push(Organizer::new("mailto:a@example.com"));
+        event.push(Attendee::new(address));
+        event.push(Sequence::new(sequence));
+        reply("-//Test//EN", event, address, PartStat::TENTATIVE, dtstamp).unwrap()
+    }
+
+    fn outcome(mut reports: Vec<Report<'static>>) -> Outcome<'static> {
+        reports.remove(0).outcome
+    }
+
+    #[test]
+    fn process_messages() {
+        let mut store = ICalendar::new("2.0", "-//Test//EN");
+        store.add_event(event());
+        let reports = process(
+            &mut store,
+            &reply_from("mailto:b@example.com", "1", "20190102T100000Z"),
+        )
+        .unwrap();
+        assert_eq!(
+            reports,
+            vec![Report {
+                uid: "uid-1".to_string(),
+                recurrence_id: None,
+                outcome: Outcome::Applied
+            }]
+        );
+        assert!(store.to_string().contains(
+            "ATTENDEE;PARTSTAT=TENTATIVE;X-DTSTAMP=20190102T100000Z:mailto:b@example.com\r\n"
+        ));
+
+        let stale = reply_from("mailto:b@example.com", "0", "20190102T100000Z");
+        assert_eq!(
+            outcome(process(&mut store, &stale).unwrap()),
+            Outcome::Stale
+        );
+        let older = reply_from("mailto:b@example.com", "1", "20181231T100000Z");
+        assert_eq!(
+            outcome(process(&mut store, &older).unwrap()),
+            Outcome::Stale
+        );
+        let newer = reply_from("mailto:b@example.com", "2", "20190102T100000Z");
+        assert_eq!(
+            outcome(process(&mut store, &newer).unwrap()),
+            Outcome::Conflict {
+                stored: 1,
+                received: 2
+            }
+        );
+        let unknown = reply_from("mailto:d@example.com", "1", "20190102T100000Z");
+        assert_eq!(
+            outcome(process(&mut store, &unknown).unwrap()),
+            Outcome::UnknownAttendee("mailto:d@example.com".to_string())
+        );
+
+        let mut proposal = event();
+        proposal.push(Location::new("Room 2"));
+        let proposal = counter("-//Test//EN", proposal).unwrap();
+        assert_eq!(
+            outcome(process(&mut store, &proposal).unwrap()),
+            Outcome::Counter(vec![Location::new("Room 2").into()])
+        );
+
+        let mut instance = event();
+        instance.push(RecurrenceID::new("20190208T100000Z"));
+        let cancellation = cancel("-//Test//EN", instance).unwrap();
+        assert_eq!(
+            outcome(process(&mut store, &cancellation).unwrap()),
+            Outcome::Applied
+        );
+        assert!(store.to_string().contains("EXDATE:20190208T100000Z\r\n"));
+
+        let cancellation = cancel("-//Test//EN", event()).unwrap();
+        assert_eq!(
+            outcome(process(&mut store, &cancellation).unwrap()),
+            Outcome::Applied
+        );
+        assert!(store.to_string().contains("STATUS:CANCELLED\r\n"));
+
+        assert_eq!(
+            process(&mut store, &ICalendar::new("2.0", "-//Test//EN")),
+            Err(Error::UnsupportedMethod {
+                method: String::new()
+            })
+        );
+    }
+
+    #[test]
+    fn bookkeeping_parameters() {
+        let mut event = Event::new("uid-1", "20190101T100000Z");
+        event.push(DtStart::new("20190201T100000Z"));
+        event.push(Summary::new("Meeting"));
+        event.push(Organizer::new("mailto:a@example.com"));
+        event.push(Sequence::new("1"));
+        let mut attendee = Attendee::new("mailto:b@example.com");
+        attendee.add(Parameter::new("partstat", "NEEDS-ACTION"));
+        attendee.add(Parameter::new("rsvp", "TRUE"));
+        event.push(attendee);
+        let mut store = ICalendar::new("2.0", "-//Test//EN");
+        store.add_event(event);
+        let reply = reply_from("mailto:b@example.com", "1", "20190102T100000Z");
+        assert_eq!(
+            outcome(process(&mut store, &reply).unwrap()),
+            Outcome::Applied
+        );
+        let stored = store.0.subcomponents[0].clone();
+        assert!(stored.to_string().contains(
+            "ATTENDEE;PARTSTAT=TENTATIVE;X-DTSTAMP=20190102T100000Z:mailto:b@example.com\r\n"
+        ));
+
+        let update = request("-//Test//EN", stored).unwrap().to_string();
+        assert!(!update.contains("X-DTSTAMP"), "{}", update);
+        assert!(update.contains("ATTENDEE;PARTSTAT=TENTATIVE:mailto:b@example.com\r\n"));
+    }
+
+    #[test]
+    fn delayed_reply() {
+        let mut store = ICalendar::new("2.0", "-//Test//EN");
+        store.add_event(event());
+        let reply_with = |status, dtstamp| {
+            reply(
+                "-//Test//EN",
+                event(),
+                "mailto:b@example.com",
+                status,
+                dtstamp,
+            )
+            .unwrap()
+        };
+        let declined = reply_with(PartStat::DECLINED, "20190103T100000Z");
+        let accepted = reply_with(PartStat::ACCEPTED, "20190102T100000Z");
+        assert_eq!(
+            outcome(process(&mut store, &declined).unwrap()),
+            Outcome::Applied
+        );
+        assert_eq!(
+            outcome(process(&mut store, &accepted).unwrap()),
+            Outcome::Stale
+        );
+        assert!(store.to_string().contains(
+            "ATTENDEE;PARTSTAT=DECLINED;X-DTSTAMP=20190103T100000Z:mailto:b@example.com\r\n"
+        ));
+
+        // Replies before the last update of the organizer are applied.
+        let early = reply_with(PartStat::TENTATIVE, "20181231T100000Z");
+        let mut store = ICalendar::new("2.0", "-//Test//EN");
+        store.add_event(event());
+        assert_eq!(
+            outcome(process(&mut store, &early).unwrap()),
+            Outcome::Applied
+        );
+    }
+
+    #[test]
+    fn invalid_components() {
+        let mut store = ICalendar::new("2.0", "-//Test//EN");
+        store.add_event(event());
+        let mut message = reply_from("mailto:b@example.com", "1", "20190102T100000Z");
+        let mut anonymous = Event::new("uid-1", "20190102T100000Z");
+        anonymous.push(Sequence::new("1"));
+        message.add_event(anonymous);
+        let mut unnamed = Event::new("uid-1", "20190102T100000Z");
+        unnamed.properties.retain(|property| property.key != "UID");
+        message.add_event(unnamed);
+
+        let reports = process(&mut store, &message).unwrap();
+        let outcomes: Vec<_> = reports.into_iter().map(|report| report.outcome).collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                Outcome::Applied,
+                Outcome::Invalid(Error::MissingProperty {
+                    method: "REPLY",
+                    property: "ATTENDEE"
+                }),
+                Outcome::Invalid(Error::MissingProperty {
+                    method: "REPLY",
+                    property: "UID"
+                })
+            ]
+        );
+        assert!(store.to_string().contains("PARTSTAT=TENTATIVE"));
+    }
+}
@@ -0,0 +1,763 @@
+//! jCal, the JSON format for iCalendar.
+//!
+//! jCal is specified in [RFC7265](https://tools.ietf.org/html/rfc7265). A
+//! component is written as array of its lowercase name, its properties and its
+//! sub-components. Each property is an array of its name, an object with its
+//! parameters, its value type and its value(s).
+//!
+//! The value type is determined by the `VALUE` parameter or the default value
+//! type of the property (see [`validation::value_type`](../validation/fn.value_type.html)).
+//! Values are converted to the JSON representation of their value type, e.g.
+//! dates are written as `2019-10-06`, integers and floats as numbers and
+//! recurrence rules as objects. Values of unknown properties are written as
+//! `unknown` strings.
+//!
+//! jCal can be read back with [`from_str`](fn.from_str.html) or
+//! [`ICalendar::from_jcal`](../struct.ICalendar.html#method.from_jcal). The
+//! JSON values are converted back into iCalendar text values and a `VALUE`
+//! parameter is added if the value type is not the default value type of the
+//! property.
+//!
+//! # Example
+//! ```
+//! use ics::properties::{DtStart, Summary};
+//! use ics::{Event, ICalendar};
+//!
+//! let mut event = Event::new("b68378cf-872d-44f1-9703-5e3725c56e71", "19960704T120000Z");
+//! event.push(DtStart::new("19960918T143000Z"));
+//! event.push(Summary::new("Networld+Interop Conference"));
+//!
+//! let mut calendar = ICalendar::new("2.0", "-//xyz Corp//NONSGML PDA Calendar Version 1.0//EN");
+//! calendar.add_event(event);
+//!
+//! assert_eq!(
+//!     calendar.to_jcal(),
+//!     concat!(
+//!         r#"["vcalendar",[["version",{},"text","2.0"],"#,
+//!         r#"["prodid",{},"text","-//xyz Corp//NONSGML PDA Calendar Version 1.0//EN"]],"#,
+//!         r#"[["vevent",[["uid",{},"text","b68378cf-872d-44f1-9703-5e3725c56e71"],"#,
+//!         r#"["dtstamp",{},"date-time","1996-07-04T12:00:00Z"],"#,
+//!         r#"["dtstart",{},"date-time","1996-09-18T14:30:00Z"],"#,
+//!         r#"["summary",{},"text","Networld+Interop Conference"]],[]]]]"#
+//!     )
+//! );
+//! ```
+use crate::components::{find_parameter, Component, Parameter, Parameters, Property};
+use crate::json::{self, Json};
+use crate::util::{
+    escape_text, quote_param_list_item, split_text_values, split_values, unescape_text,
+};
+use crate::validation::{self, ValueType};
+use crate::values::RequestStatusValue;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Converts a component with its properties and sub-components into jCal.
+pub fn to_string(component: &Component) -> String {
+    component_to_json(component).to_string()
+}
+
+/// Writes a component with its properties and sub-components as jCal to a
+/// writer.
+pub fn write<W>(component: &Component, mut writer: W) -> io::Result<()>
+where
+    W: Write,
+{
+    write!(writer, "{}", component_to_json(component))
+}
+
+pub(crate) fn component_to_json<'a>(component: &'a Component<'a>) -> Json<'a> {
+    Json::Array(vec![
+        Json::String(lowercase(&component.name)),
+        Json::Array(component.properties.iter().map(property_to_json).collect()),
+        Json::Array(
+            component
+                .subcomponents
+                .iter()
+                .map(component_to_json)
+                .collect(),
+        ),
+    ])
+}
+
+fn property_to_json<'a>(property: &'a Property<'a>) -> Json<'a> {
+    let parameters = property
+        .parameters
+        .iter()
+        .filter(|(key, _)| !key.eq_ignore_ascii_case("VALUE"))
+        .map(|(key, value)| {
+            let parameter = Parameter::new(key.as_ref(), value.as_ref());
+            let mut values = parameter.values();
+            let value = if values.len() == 1 {
+                Json::String(Cow::Owned(values.remove(0).into_owned()))
+            } else {
+                Json::Array(
+                    values
+                        .into_iter()
+                        .map(|value| Json::String(Cow::Owned(value.into_owned())))
+                        .collect(),
+                )
+            };
+            (lowercase(key), value)
+        })
+        .collect();
+
+    let value_type = validation::value_type(property);
+    // Unregistered value types keep their name
+    let type_name = match (value_type, find_parameter(&property.parameters, "VALUE")) {
+        (Some(value_type), _) => Cow::Owned(value_type.name().to_ascii_lowercase()),
+        (None, Some(name)) => Cow::Owned(name.to_ascii_lowercase()),
+        (None, None) => Cow::Borrowed("unknown"),
+    };
+    let mut json = vec![
+        Json::String(lowercase(&property.key)),
+        Json::Object(parameters),
+        Json::String(type_name),
+    ];
+    json.extend(values_to_json(property, value_type));
+    Json::Array(json)
+}
+
+fn values_to_json<'a>(property: &'a Property<'a>, value_type: Option<ValueType>) -> Vec<Json<'a>> {
+    let value = property.value.as_ref();
+    let value_type = match value_type {
+        Some(value_type) => value_type,
+        None => return vec![Json::String(Cow::Borrowed(value))],
+    };
+    if property.key.eq_ignore_ascii_case("GEO") {
+        let coordinates: Vec<_> = value.split(';').collect();
+        if coordinates.len() == 2 && coordinates.iter().all(|c| validation::is_float(c)) {
+            return vec![Json::Array(
+                coordinates.into_iter().map(float_to_json).collect(),
+            )];
+        }
+    }
+    if property.key.eq_ignore_ascii_case("REQUEST-STATUS") {
+        if let Ok(status) = RequestStatusValue::parse(value) {
+            let mut parts = vec![
+                Json::String(Cow::Owned(status.code.to_string())),
+                Json::String(status.description),
+            ];
+            parts.extend(status.data.map(Json::String));
+            return vec![Json::Array(parts)];
+        }
+    }
+    if validation::is_list(&property.key) {
+        if value_type == ValueType::Text {
+            return split_text_values(value)
+                .into_iter()
+                .map(Json::String)
+                .collect();
+        }
+        return split_values(value)
+            .into_iter()
+            .map(|value| value_to_json(value, value_type))
+            .collect();
+    }
+    vec![value_to_json(Cow::Borrowed(value), value_type)]
+}
+
+fn value_to_json(value: Cow<'_, str>, value_type: ValueType) -> Json<'_> {
+    match value_type {
+        ValueType::Boolean if value.eq_ignore_ascii_case("TRUE") => Json::Bool(true),
+        ValueType::Boolean if value.eq_ignore_ascii_case("FALSE") => Json::Bool(false),
+        ValueType::Integer => match value.parse::<i64>() {
+            Ok(number) => Json::Number(Cow::Owned(number.to_string())),
+            Err(_) => Json::String(value),
+        },
+        ValueType::Float if validation::is_float(&value) => float_to_json(&value),
+        ValueType::Date | ValueType::DateTime => Json::String(format_date_time(value)),
+        ValueType::Time => Json::String(format_time(value)),
+        ValueType::UtcOffset => Json::String(format_utc_offset(value)),
+        ValueType::Period => Json::String(format_period(value)),
+        ValueType::Recur => recur_to_json(value),
+        ValueType::Text => match value {
+            Cow::Borrowed(value) => Json::String(unescape_text(value)),
+            Cow::Owned(value) => Json::String(Cow::Owned(unescape_text(&value).into_owned())),
+        },
+        _ => Json::String(value),
+    }
+}
+
+fn float_to_json<'a>(value: &str) -> Json<'a> {
+    match value.parse::<f64>() {
+        Ok(number) => Json::Number(Cow::Owned(number.to_string())),
+        Err(_) => Json::String(Cow::Owned(value.to_string())),
+    }
+}
+
+// 19970714 -> 1997-07-14 and 19970714T133000Z -> 1997-07-14T13:30:00Z
+fn format_date_time(value: Cow<'_, str>) -> Cow<'_, str> {
+    let (date, time) = match value.find('T') {
+        Some(index) => (&value[..index], Some(&value[index + 1..])),
+        None => (&value[..], None),
+    };
+    if date.len() != 8 || !date.bytes().all(|b| b.is_ascii_digit()) {
+        return value;
+    }
+    let mut output = format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..]);
+    if let Some(time) = time {
+        output.push('T');
+        output.push_str(&format_time(Cow::Borrowed(time)));
+    }
+    Cow::Owned(output)
+}
+
+// 133000Z -> 13:30:00Z
+fn format_time(value: Cow<'_, str>) -> Cow<'_, str> {
+    let digits = value.trim_end_matches('Z');
+    if digits.len() != 6 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return value;
+    }
+    Cow::Owned(format!(
+        "{}:{}:{}{}",
+        &digits[..2],
+        &digits[2..4],
+        &digits[4..],
+        &value[6..]
+    ))
+}
+
+// -0500 -> -05:00
+fn format_utc_offset(value: Cow<'_, str>) -> Cow<'_, str> {
+    if !validation::is_utc_offset(&value) {
+        return value;
+    }
+    let mut output = format!("{}:{}", &value[..3], &value[3..5]);
+    if value.len() == 7 {
+        output.push(':');
+        output.push_str(&value[5..]);
+    }
+    Cow::Owned(output)
+}
+
+// The start and an explicit end of a period are date-times, the duration is
+// kept.
+fn format_period(value: Cow<'_, str>) -> Cow<'_, str> {
+    let (start, end) = match value.find('/') {
+        Some(index) => (&value[..index], &value[index + 1..]),
+        None => return value,
+    };
+    let end = if end.starts_with('P') || end.starts_with('+') || end.starts_with('-') {
+        Cow::Borrowed(end)
+    } else {
+        format_date_time(Cow::Borrowed(end))
+    };
+    Cow::Owned(format!(
+        "{}/{}",
+        format_date_time(Cow::Borrowed(start)),
+        end
+    ))
+}
+
+// Rule parts with numbers as values
+const NUMERIC_RULE_PARTS: [&str; 10] = [
+    "COUNT",
+    "INTERVAL",
+    "BYSECOND",
+    "BYMINUTE",
+    "BYHOUR",
+    "BYMONTHDAY",
+    "BYYEARDAY",
+    "BYWEEKNO",
+    "BYMONTH",
+    "BYSETPOS",
+];
+
+// FREQ=YEARLY;BYDAY=-1SU,2MO -> {"freq":"YEARLY","byday":["-1SU","2MO"]}
+fn recur_to_json(value: Cow<'_, str>) -> Json<'_> {
+    let mut members = Vec::new();
+    for part in value.split(';').filter(|part| !part.is_empty()) {
+        let (key, value) = match part.find('=') {
+            Some(index) => (&part[..index], &part[index + 1..]),
+            None => (part, ""),
+        };
+        let is_numeric = NUMERIC_RULE_PARTS
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(key));
+        let mut values: Vec<_> = value
+            .split(',')
+            .map(|value| {
+                if key.eq_ignore_ascii_case("UNTIL") {
+                    Json::String(Cow::Owned(
+                        format_date_time(Cow::Borrowed(value)).into_owned(),
+                    ))
+                } else if is_numeric && value.parse::<i64>().is_ok() {
+                    Json::Number(Cow::Owned(value.trim_start_matches('+').to_string()))
+                } else {
+                    Json::String(Cow::Owned(value.to_string()))
+                }
+            })
+            .collect();
+        let value = if values.len() == 1 {
+            values.remove(0)
+        } else {
+            Json::Array(values)
+        };
+        members.push((Cow::Owned(key.to_ascii_lowercase()), value));
+    }
+    Json::Object(members)
+}
+
+fn lowercase(name: &str) -> Cow<'_, str> {
+    if name.bytes().any(|b| b.is_ascii_uppercase()) {
+        Cow::Owned(name.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+/// The error type for reading jCal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// The input is not valid JSON.
+    Syntax {
+        /// Byte offset of the invalid input
+        offset: usize,
+    },
+    /// The JSON does not have the structure of jCal.
+    InvalidStructure {
+        /// Description of the expected element, e.g. `property`
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Syntax { offset } => write!(f, "invalid JSON at byte {}", offset),
+            Error::InvalidStructure { expected } => {
+                write!(f, "invalid jCal: expected {}", expected)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Parses jCal into a component with its properties and sub-components.
+///
+/// # Example
+/// ```
+/// use ics::jcal;
+///
+/// let component = jcal::from_str(r#"["vtodo",[["percent-complete",{},"integer",50]],[]]"#).unwrap();
+/// assert_eq!(component.to_string(), "BEGIN:VTODO\r\nPERCENT-COMPLETE:50\r\nEND:VTODO\r\n");
+/// ```
+pub fn from_str(input: &str) -> Result<Component<'_>, Error> {
+    let json = json::parse(input).map_err(|offset| Error::Syntax { offset })?;
+    component_from_json(json)
+}
+
+fn invalid(expected: &'static str) -> Error {
+    Error::InvalidStructure { expected }
+}
+
+pub(crate) fn component_from_json(json: Json<'_>) -> Result<Component<'_>, Error> {
+    let mut parts = match json {
+        Json::Array(parts) => parts.into_iter(),
+        _ => return Err(invalid("component")),
+    };
+    let name = match parts.next() {
+        Some(Json::String(name)) => uppercase(name),
+        _ => return Err(invalid("component name")),
+    };
+    let properties = match parts.next() {
+        Some(Json::Array(properties)) => properties
+            .into_iter()
+            .map(property_from_json)
+            .collect::<Result<_, _>>()?,
+        _ => return Err(invalid("properties")),
+    };
+    let subcomponents = match parts.next() {
+        Some(Json::Array(components)) => components
+            .into_iter()
+            .map(component_from_json)
+            .collect::<Result<_, _>>()?,
+        _ => return Err(invalid("components")),
+    };
+    if parts.next().is_some() {
+        return Err(invalid("end of component"));
+    }
+    Ok(Component {
+        name,
+        properties,
+        subcomponents,
+    })
+}
+
+fn property_from_json(json: Json<'_>) -> Result<Property<'_>, Error> {
+    let mut parts = match json {
+        Json::Array(parts) => parts.into_iter(),
+        _ => return Err(invalid("property")),
+    };
+    let key = match parts.next() {
+        Some(Json::String(name)) => uppercase(name),
+        _ => return Err(invalid("property name")),
+    };
+    let mut parameters = match parts.next() {
+        Some(Json::Object(members)) => parameters_from_json(members)?,
+        _ => return Err(invalid("parameters")),
+    };
+    let type_name = match parts.next() {
+        Some(Json::String(name)) => name,
+        _ => return Err(invalid("value type")),
+    };
+    let value_type = ValueType::from_name(&type_name);
+    if let Some(value_type) = value_type {
+        let default = validation::value_types(&key).map(|value_types| value_types[0]);
+        if default != Some(value_type) {
+            parameters.insert("VALUE".into(), value_type.name().into());
+        }
+    } else if !type_name.eq_ignore_ascii_case("unknown") {
+        parameters.insert("VALUE".into(), uppercase(type_name));
+    }
+    let mut values = parts
+        .map(|value| value_from_json(value, value_type))
+        .collect::<Result<Vec<_>, _>>()?;
+    let value = match values.len() {
+        0 => return Err(invalid("property value")),
+        1 => values.remove(0),
+        _ => Cow::Owned(values.join(",")),
+    };
+    Ok(Property {
+        key,
+        value,
+        parameters,
+    })
+}
+
+fn parameters_from_json<'a>(
+    members: Vec<(Cow<'a, str>, Json<'a>)>,
+) -> Result<Parameters<'a>, Error> {
+    let mut parameters = BTreeMap::new();
+    for (key, value) in members {
+        let value = match value {
+            Json::String(value) => value,
+            Json::Array(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| match value {
+                        Json::String(value) => Ok(quote_param_list_item(value)),
+                        _ => Err(invalid("parameter value")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Cow::Owned(values.join(","))
+            }
+            _ => return Err(invalid("parameter value")),
+        };
+        parameters.insert(uppercase(key), value);
+    }
+    Ok(parameters)
+}
+
+fn value_from_json(json: Json<'_>, value_type: Option<ValueType>) -> Result<Cow<'_, str>, Error> {
+    match json {
+        Json::Null => Ok(Cow::Borrowed("")),
+        Json::Bool(true) => Ok(Cow::Borrowed("TRUE")),
+        Json::Bool(false) => Ok(Cow::Borrowed("FALSE")),
+        Json::Number(value) => parse_number(value, value_type == Some(ValueType::Integer)),
+        Json::String(value) => Ok(match value_type {
+            Some(ValueType::Text) => escape_text(value),
+            Some(ValueType::Date) | Some(ValueType::DateTime) => parse_date_time(value),
+            Some(ValueType::Time) | Some(ValueType::UtcOffset) => remove(value, ':'),
+            Some(ValueType::Period) => parse_period(value),
+            _ => value,
+        }),
+        // structured values like GEO or REQUEST-STATUS
+        Json::Array(values) => {
+            let values = values
+                .into_iter()
+                .map(|value| value_from_json(value, value_type))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Cow::Owned(values.join(";")))
+        }
+        Json::Object(members) => recur_from_json(members),
+    }
+}
+
+// 1997-07-14T13:30:00Z -> 19970714T133000Z
+fn parse_date_time(value: Cow<'_, str>) -> Cow<'_, str> {
+    remove(remove(value, '-'), ':')
+}
+
+fn parse_period(value: Cow<'_, str>) -> Cow<'_, str> {
+    let (start, end) = match value.find('/') {
+        Some(index) => (&value[..index], &value[index + 1..]),
+        None => return value,
+    };
+    let end = if end.starts_with('P') || end.starts_with('+') || end.starts_with('-') {
+        Cow::Borrowed(end)
+    } else {
+        parse_date_time(Cow::Borrowed(end))
+    };
+    Cow::Owned(format!("{}/{}", parse_date_time(Cow::Borrowed(start)), end))
+}
+
+// {"freq":"YEARLY","byday":["-1SU","2MO"]} -> FREQ=YEARLY;BYDAY=-1SU,2MO
+fn recur_from_json<'a>(members: Vec<(Cow<'a, str>, Json<'a>)>) -> Result<Cow<'a, str>, Error> {
+    let mut parts = Vec::with_capacity(members.len());
+    for (key, value) in members {
+        let values = match value {
+            Json::Array(values) => values,
+            value => vec![value],
+        };
+        let values = values
+            .into_iter()
+            .map(|value| match value {
+                Json::String(value) if key.eq_ignore_ascii_case("until") => {
+                    Ok(parse_date_time(value))
+                }
+                Json::String(value) => Ok(value),
+                Json::Number(value) => parse_number(value, true),
+                _ => Err(invalid("recurrence rule part")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        parts.push(format!("{}={}", key.to_ascii_uppercase(), values.join(",")));
+    }
+    Ok(Cow::Owned(parts.join(";")))
+}
+
+// JSON numbers can have an exponent which INTEGER and FLOAT values cannot
+// have, e.g. 1.5e2 -> 150. Fractions are not allowed for integers.
+fn parse_number(value: Cow<'_, str>, is_integer: bool) -> Result<Cow<'_, str>, Error> {
+    let is_plain = !value.contains(|c| c == '.' || c == 'e' || c == 'E');
+    if is_plain && (!is_integer || value.parse::<i32>().is_ok()) {
+        return Ok(value);
+    }
+    let (sign, number) = if value.starts_with('-') {
+        ("-", &value[1..])
+    } else {
+        ("", &value[..])
+    };
+    let (mantissa, exponent) = match number.find(|c| c == 'e' || c == 'E') {
+        Some(index) => (&number[..index], &number[index + 1..]),
+        None => (number, "0"),
+    };
+    // Larger exponents would create huge values.
+    let exponent = match exponent.trim_start_matches('+').parse::<i16>() {
+        Ok(exponent) if exponent.abs() <= 1000 => i64::from(exponent),
+        _ => return Err(invalid("number")),
+    };
+    let (integer, fraction) = match mantissa.find('.') {
+        Some(index) => (&mantissa[..index], &mantissa[index + 1..]),
+        None => (mantissa, ""),
+    };
+    let digits = format!("{}{}", integer, fraction);
+    let point = integer.len() as i64 + exponent;
+    let (integer, fraction) = if point <= 0 {
+        (
+            String::new(),
+            format!("{}{}", "0".repeat(-point as usize), digits),
+        )
+    } else if point as usize >= digits.len() {
+        let zeros = "0".repeat(point as usize - digits.len());
+        (format!("{}{}", digits, zeros), String::new())
+    } else {
+        let (integer, fraction) = digits.split_at(point as usize);
+        (integer.to_string(), fraction.to_string())
+    };
+    let integer = match integer.trim_start_matches('0') {
+        "" => "0",
+        integer => integer,
+    };
+    let fraction = fraction.trim_end_matches('0');
+    let sign = if integer == "0" && fraction.is_empty() {
+        ""
+    } else {
+        sign
+    };
+    if is_integer {
+        let value = format!("{}{}", sign, integer);
+        if !fraction.is_empty() || value.parse::<i32>().is_err() {
+            return Err(invalid("integer"));
+        }
+        Ok(Cow::Owned(value))
+    } else if fraction.is_empty() {
+        Ok(Cow::Owned(format!("{}{}", sign, integer)))
+    } else {
+        Ok(Cow::Owned(format!("{}{}.{}", sign, integer, fraction)))
+    }
+}
+
+fn remove(value: Cow<'_, str>, c: char) -> Cow<'_, str> {
+    if value.contains(c) {
+        Cow::Owned(value.replace(c, ""))
+    } else {
+        value
+    }
+}
+
+fn uppercase(name: Cow<'_, str>) -> Cow<'_, str> {
+    if name.bytes().any(|b| b.is_ascii_lowercase()) {
+        Cow::Owned(name.to_ascii_uppercase())
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_str, to_string, Error};
+    use crate::components::{Component, Property};
+    use crate::parameters::{DelegatedTo, TzIDParam, Value};
+
+    fn property(property: Property) -> String {
+        let mut component = Component::new("X");
+        component.add_property(property);
+        let json = to_string(&component);
+        json["[\"x\",[".len()..json.len() - "],[]]".len()].to_string()
+    }
+
+    #[test]
+    fn values() {
+        let mut dtstart = Property::new("DTSTART", "20190101");
+        dtstart.add(Value::DATE);
+        assert_eq!(property(dtstart), r#"["dtstart",{},"date","2019-01-01"]"#);
+
+        let mut dtend = Property::new("DTEND", "20190101T100000");
+        dtend.add(TzIDParam::new("Europe/Berlin"));
+        assert_eq!(
+            property(dtend),
+            r#"["dtend",{"tzid":"Europe/Berlin"},"date-time","2019-01-01T10:00:00"]"#
+        );
+        assert_eq!(
+            property(Property::new("CATEGORIES", "A\\,B,C")),
+            r#"["categories",{},"text","A,B","C"]"#
+        );
+        assert_eq!(
+            property(Property::new("GEO", "37.386013;-122.082932")),
+            r#"["geo",{},"float",[37.386013,-122.082932]]"#
+        );
+        assert_eq!(
+            property(Property::new("PRIORITY", "1")),
+            r#"["priority",{},"integer",1]"#
+        );
+        assert_eq!(
+            property(Property::new("TZOFFSETFROM", "-0500")),
+            r#"["tzoffsetfrom",{},"utc-offset","-05:00"]"#
+        );
+        assert_eq!(
+            property(Property::new("REQUEST-STATUS", "2.0;Success")),
+            r#"["request-status",{},"text",["2.0","Success"]]"#
+        );
+        assert_eq!(
+            property(Property::new("FREEBUSY", "19970308T160000Z/PT3H")),
+            r#"["freebusy",{},"period","1997-03-08T16:00:00Z/PT3H"]"#
+        );
+        assert_eq!(
+            property(Property::new(
+                "RRULE",
+                "FREQ=YEARLY;UNTIL=20000101;BYDAY=-1SU,2MO;BYMONTH=10"
+            )),
+            r#"["rrule",{},"recur",{"freq":"YEARLY","until":"2000-01-01","byday":["-1SU","2MO"],"bymonth":10}]"#
+        );
+        assert_eq!(
+            property(Property::boolean("X-FLAG", true)),
+            r#"["x-flag",{},"boolean",true]"#
+        );
+        assert_eq!(
+            property(Property::new("X-CUSTOM", "a\\,b")),
+            r#"["x-custom",{},"unknown","a\\,b"]"#
+        );
+        let mut custom = Property::new("X-CUSTOM", "a\\,b");
+        custom.add(Value::new("X-NAME"));
+        assert_eq!(property(custom), r#"["x-custom",{},"x-name","a\\,b"]"#);
+    }
+
+    #[test]
+    fn parameters() {
+        let mut attendee = Property::new("ATTENDEE", "mailto:a@example.com");
+        attendee.add(DelegatedTo::new("mailto:b@example.com"));
+        attendee.add(DelegatedTo::new("mailto:c@example.com"));
+        assert_eq!(
+            property(attendee),
+            r#"["attendee",{"delegated-to":["mailto:b@example.com","mailto:c@example.com"]},"cal-address","mailto:a@example.com"]"#
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut component = Component::new("VEVENT");
+        let mut dtstart = Property::new("DTSTART", "20190101");
+        dtstart.add(Value::DATE);
+        component.add_property(dtstart);
+        let mut attendee = Property::new("ATTENDEE", "mailto:a@example.com");
+        attendee.add(DelegatedTo::new("mailto:b@example.com"));
+        attendee.add(DelegatedTo::new("mailto:c@example.com"));
+        component.add_property(attendee);
+        for (key, value) in &[
+            ("DTEND", "20190101T100000Z"),
+            ("SUMMARY", "a\\, b\\; c\\nd"),
+            ("CATEGORIES", "A\\,B,C"),
+            ("GEO", "37.386013;-122.082932"),
+            ("PRIORITY", "1"),
+            ("TZOFFSETFROM", "-0500"),
+            ("REQUEST-STATUS", "3.1;Invalid\\; value;DTSTART:96-Apr-01"),
+            (
+                "FREEBUSY",
+                "19970308T160000Z/PT3H,19970308T200000Z/19970308T210000Z",
+            ),
+            (
+                "RRULE",
+                "FREQ=YEARLY;UNTIL=20000101T000000Z;BYDAY=-1SU,2MO;BYMONTH=10",
+            ),
+            ("X-CUSTOM", "a\\,b"),
+        ] {
+            component.add_property(Property::new(*key, *value));
+        }
+        component.add_property(Property::boolean("X-FLAG", true));
+        let mut custom = Property::new("X-CUSTOM", "a\\,b");
+        custom.add(Value::new("X-NAME"));
+        component.add_property(custom);
+        component.add_component(Component::new("VALARM"));
+
+        let json = to_string(&component);
+        assert_eq!(from_str(&json), Ok(component));
+    }
+
+    #[test]
+    fn numbers() {
+        let json = r#"["x",[
+            ["geo",{},"float",[1e2,2.5E-1]],
+            ["priority",{},"integer",1.0e1],
+            ["x-f",{},"float",-0.0e0],
+            ["x-g",{},"float",-12.50],
+            ["rrule",{},"recur",{"freq":"DAILY","count":1E1}]
+        ],[]]"#;
+        assert_eq!(
+            from_str(json).unwrap().to_string(),
+            "BEGIN:X\r\nGEO:100;0.25\r\nPRIORITY:10\r\nX-F;VALUE=FLOAT:0\r\n\
+             X-G;VALUE=FLOAT:-12.5\r\nRRULE:FREQ=DAILY;COUNT=10\r\nEND:X\r\n"
+        );
+        assert!(from_str(r#"["x",[["priority",{},"integer",1.5]],[]]"#).is_err());
+        assert!(from_str(r#"["x",[["priority",{},"integer",1e10]],[]]"#).is_err());
+        assert!(from_str(r#"["x",[["geo",{},"float",[1e9999,0]]],[]]"#).is_err());
+        assert!(
+            from_str(r#"["x",[["rrule",{},"recur",{"freq":"DAILY","count":0.5}]],[]]"#).is_err()
+        );
+    }
+
+    #[test]
+    fn syntax_error() {
+        assert_eq!(from_str("[\"vevent\","), Err(Error::Syntax { offset: 10 }));
+    }
+
+    #[test]
+    fn property_without_value() {
+        assert!(from_str(r#"["vevent",[["summary",{},"text"]],[]]"#).is_err());
+    }
+
+    #[test]
+    fn component_with_extra_member() {
+        assert!(from_str(r#"["vevent",[],[],[]]"#).is_err());
+    }
+
+    #[test]
+    fn non_string_parameter() {
+        assert!(from_str(r#"["vevent",[["x",{"a":1},"text","b"]],[]]"#).is_err());
+    }
+}
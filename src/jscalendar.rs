@@ -0,0 +1,1347 @@
+//! JSCalendar, the JSON data model for calendars used by JMAP.
+//!
+//! JSCalendar is specified in [RFC8984](https://tools.ietf.org/html/rfc8984).
+//! This module converts an [`Event`](../struct.Event.html) into a JSCalendar
+//! `Event` object and a [`ToDo`](../struct.ToDo.html) into a `Task` object and
+//! back, following the mapping of [RFC9555](https://tools.ietf.org/html/rfc9555):
+//! - `SUMMARY`, `DESCRIPTION`, `LOCATION`, `CATEGORIES`, `CLASS`, `TRANSP`,
+//!   `STATUS`, `PRIORITY`, `PERCENT-COMPLETE`, `SEQUENCE`, `COLOR`,
+//!   `CREATED` and `DTSTAMP` map to the respective JSCalendar properties.
+//! - `DTSTART` and `DUE` map to local date-times with the `TZID` parameter as
+//!   `timeZone`. UTC date-times use the `Etc/UTC` time zone and dates set
+//!   `showWithoutTime`. Both must have the same time zone.
+//! - `DTEND` is converted into a `duration`, so it must be in the time zone of
+//!   `DTSTART`. Converting back always creates a `DURATION` property.
+//! - `RRULE` maps to `recurrenceRules`, `RDATE` and `EXDATE` to
+//!   `recurrenceOverrides`.
+//! - `ORGANIZER` and `ATTENDEE` map to `participants` and `replyTo`.
+//! - `VALARM` components with the `DISPLAY` or `EMAIL` action map to
+//!   `alerts`. Converting back always creates `DISPLAY` alarms because email
+//!   alerts have no recipients.
+//!
+//! Time zones are referenced by their name. `VTIMEZONE` definitions are not
+//! converted, so the names should be IANA time zone names. Properties without
+//! a JSCalendar equivalent are dropped.
+//!
+//! # Example
+//! ```
+//! use ics::jscalendar;
+//! use ics::parameters::TzIDParam;
+//! use ics::properties::{DtStart, Duration, Summary};
+//! use ics::Event;
+//!
+//! let mut event = Event::new("a8df6573-0474-496d-8496-033ad45d7fea", "20200102T182304Z");
+//! event.push(Summary::new("Some event"));
+//! let mut dtstart = DtStart::new("20200102T100000");
+//! dtstart.add(TzIDParam::new("Europe/Berlin"));
+//! event.push(dtstart);
+//! event.push(Duration::new("PT1H"));
+//!
+//! let json = jscalendar::event_to_string(&event).unwrap();
+//! assert_eq!(
+//!     json,
+//!     concat!(
+//!         r#"{"@type":"Event","uid":"a8df6573-0474-496d-8496-033ad45d7fea","#,
+//!         r#""updated":"2020-01-02T18:23:04Z","title":"Some event","#,
+//!         r#""start":"2020-01-02T10:00:00","timeZone":"Europe/Berlin","duration":"PT1H"}"#
+//!     )
+//! );
+//! assert_eq!(jscalendar::event_from_str(&json).unwrap(), event);
+//! ```
+use crate::components::{find_parameter, Property};
+use crate::ical::{Alarm, Event, ToDo};
+use crate::json::{self, Json};
+use crate::parameters::{CUType, PartStat, Related, Role, TzIDParam, Value, CN, RSVP};
+use crate::properties::{
+    Attendee, Categories, Class, Created, Description, Duration, Location, Organizer,
+    PercentComplete, Priority, RRule, Sequence, Status, Summary, Transp, Trigger,
+};
+use crate::util::{escape_text, split_text_values, split_values, unescape_text};
+use std::borrow::Cow;
+use std::error;
+use std::fmt;
+
+/// Converts an event into a JSCalendar `Event` object.
+pub fn event_to_string(event: &Event) -> Result<String, Error> {
+    to_json("Event", &event.properties, &event.alarms).map(|json| json.to_string())
+}
+
+/// Converts a to-do into a JSCalendar `Task` object.
+pub fn todo_to_string(todo: &ToDo) -> Result<String, Error> {
+    to_json("Task", &todo.properties, &todo.alarms).map(|json| json.to_string())
+}
+
+/// The error type for converting JSCalendar.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// The input is not valid JSON.
+    Syntax {
+        /// Byte offset of the invalid input
+        offset: usize,
+    },
+    /// The JSON is not a JSCalendar object of the expected type or a required
+    /// property is missing.
+    InvalidStructure {
+        /// Description of the expected element, e.g. `uid`
+        expected: &'static str,
+    },
+    /// A property cannot be converted without changing its meaning, e.g.
+    /// because the conversion would require time zone definitions.
+    Unsupported {
+        /// Name of the property, e.g. `RRULE` or `until`
+        property: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Syntax { offset } => write!(f, "invalid JSON at byte {}", offset),
+            Error::InvalidStructure { expected } => {
+                write!(f, "invalid JSCalendar: expected {}", expected)
+            }
+            Error::Unsupported { property } => write!(f, "cannot convert {}", property),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Reads an event from a JSCalendar `Event` object. The `uid` and `updated`
+/// properties are required.
+pub fn event_from_str(input: &str) -> Result<Event<'static>, Error> {
+    let object = parse(input, "Event")?;
+    let (uid, dtstamp) = required(&object)?;
+    let mut event = Event::new(uid, dtstamp);
+    let (properties, alarms) = from_json(&object, false)?;
+    event.properties.extend(properties);
+    event.alarms = alarms;
+    Ok(event)
+}
+
+/// Reads a to-do from a JSCalendar `Task` object. The `uid` and `updated`
+/// properties are required.
+pub fn todo_from_str(input: &str) -> Result<ToDo<'static>, Error> {
+    let object = parse(input, "Task")?;
+    let (uid, dtstamp) = required(&object)?;
+    let mut todo = ToDo::new(uid, dtstamp);
+    let (properties, alarms) = from_json(&object, true)?;
+    todo.properties.extend(properties);
+    todo.alarms = alarms;
+    Ok(todo)
+}
+
+type Members<'a> = Vec<(Cow<'a, str>, Json<'a>)>;
+
+fn string<'a, S: Into<String>>(value: S) -> Json<'a> {
+    Json::String(Cow::Owned(value.into()))
+}
+
+// Numbers are only written if they are valid integers.
+fn integer<'a>(value: &str) -> Option<Json<'a>> {
+    let value = if value.starts_with('+') {
+        &value[1..]
+    } else {
+        value
+    };
+    let number = value.parse::<i64>().ok()?;
+    Some(Json::Number(Cow::Owned(number.to_string())))
+}
+
+fn unsupported(property: &'static str) -> Error {
+    Error::Unsupported { property }
+}
+
+fn is_utc(time_zone: Option<&str>) -> bool {
+    time_zone == Some("Etc/UTC") || time_zone == Some("UTC")
+}
+
+fn object<'a>(members: Vec<(&'static str, Json<'a>)>) -> Json<'a> {
+    Json::Object(
+        members
+            .into_iter()
+            .map(|(key, value)| (Cow::Borrowed(key), value))
+            .collect(),
+    )
+}
+
+// Returns the value of a parameter.
+fn parameter(property: &Property, key: &str) -> Option<String> {
+    find_parameter(&property.parameters, key).map(|value| value.to_string())
+}
+
+// A date or date-time split into its local date-time and time zone
+struct DateTime {
+    local: String,
+    time_zone: Option<String>,
+    is_date: bool,
+}
+
+// 19970714T133000Z -> 1997-07-14T13:30:00 in Etc/UTC
+fn date_time(value: &str, tzid: Option<String>) -> Option<DateTime> {
+    let (date, time) = match value.find('T') {
+        Some(index) => (&value[..index], &value[index + 1..]),
+        None => (value, ""),
+    };
+    let digits = |s: &str, len| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    if !digits(date, 8) {
+        return None;
+    }
+    let is_utc = time.ends_with('Z');
+    let time = time.trim_end_matches('Z');
+    if !time.is_empty() && !digits(time, 6) {
+        return None;
+    }
+    let time = if time.is_empty() { "000000" } else { time };
+    Some(DateTime {
+        local: format!(
+            "{}-{}-{}T{}:{}:{}",
+            &date[..4],
+            &date[4..6],
+            &date[6..],
+            &time[..2],
+            &time[2..4],
+            &time[4..]
+        ),
+        time_zone: if is_utc { Some("Etc/UTC".into()) } else { tzid },
+        is_date: value.len() == 8,
+    })
+}
+
+fn property_date_time(property: &Property) -> Option<DateTime> {
+    date_time(&property.value, parameter(property, "TZID"))
+}
+
+// DTSTAMP, CREATED and absolute triggers must be UTC date-times.
+fn utc_date_time(value: &str) -> Option<String> {
+    match date_time(value, None) {
+        Some(date_time) if value.ends_with('Z') && !date_time.is_date => {
+            Some(date_time.local + "Z")
+        }
+        _ => None,
+    }
+}
+
+// Seconds since 1970-01-01 of a local date-time (1997-07-14T13:30:00)
+fn timestamp(local: &str) -> Option<i64> {
+    let field = |range: std::ops::Range<usize>| local.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+    // days from civil, see http://howardhinnant.github.io/date_algorithms.html
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+fn format_duration(seconds: i64) -> String {
+    if seconds == 0 {
+        return "PT0S".into();
+    }
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let mut output = String::from("P");
+    if days > 0 {
+        output.push_str(&format!("{}D", days));
+    }
+    if hours > 0 || minutes > 0 || seconds > 0 {
+        output.push('T');
+        for &(value, unit) in &[(hours, 'H'), (minutes, 'M'), (seconds, 'S')] {
+            if value > 0 {
+                output.push_str(&format!("{}{}", value, unit));
+            }
+        }
+    }
+    output
+}
+
+// Converts the properties and alarms of an event or to-do into a JSCalendar
+// object of the given type.
+fn to_json<'a>(
+    object_type: &'static str,
+    properties: &'a [Property],
+    alarms: &'a [Alarm],
+) -> Result<Json<'a>, Error> {
+    let is_task = object_type == "Task";
+    // Recurrence dates are local date-times in the time zone of the start.
+    let start_zone = properties
+        .iter()
+        .find(|property| property.key.eq_ignore_ascii_case("DTSTART"))
+        .and_then(property_date_time)
+        .and_then(|date_time| date_time.time_zone);
+    let start_zone = start_zone.as_ref().map(String::as_str);
+    let mut members: Vec<(&'static str, Json)> = vec![("@type", Json::String(object_type.into()))];
+    let mut start = None;
+    let mut zone: Option<(Option<String>, bool)> = None;
+    let mut end = None;
+    let mut keywords = Members::new();
+    let mut locations = Members::new();
+    let mut rules = Vec::new();
+    let mut overrides = Members::new();
+    let mut participants: Vec<(String, Members)> = Vec::new();
+    let mut reply_to = None;
+
+    for property in properties {
+        let value = property.value.as_ref();
+        let text = || Json::String(unescape_text(value));
+        match property.key.to_ascii_uppercase().as_str() {
+            "UID" => members.push(("uid", Json::String(value.into()))),
+            "DTSTAMP" | "CREATED" => {
+                let (key, name) = if property.key.eq_ignore_ascii_case("CREATED") {
+                    ("created", "CREATED")
+                } else {
+                    ("updated", "DTSTAMP")
+                };
+                let date_time = utc_date_time(value).ok_or_else(|| unsupported(name))?;
+                members.push((key, string(date_time)));
+            }
+            "SEQUENCE" => members.extend(integer(value).map(|value| ("sequence", value))),
+            "PRIORITY" => members.extend(integer(value).map(|value| ("priority", value))),
+            "PERCENT-COMPLETE" if is_task => {
+                members.extend(integer(value).map(|value| ("percentComplete", value)))
+            }
+            "SUMMARY" => members.push(("title", text())),
+            "DESCRIPTION" => members.push(("description", text())),
+            "COLOR" => members.push(("color", text())),
+            "DTSTART" | "DUE" => {
+                if let Some(date_time) = property_date_time(property) {
+                    let (key, name) = if property.key.eq_ignore_ascii_case("DUE") {
+                        ("due", "DUE")
+                    } else {
+                        start = Some(date_time.local.clone());
+                        ("start", "DTSTART")
+                    };
+                    members.push((key, string(date_time.local)));
+                    // The start and the due date share the time zone
+                    let date_zone = (date_time.time_zone, date_time.is_date);
+                    match zone {
+                        Some(ref zone) if *zone != date_zone => return Err(unsupported(name)),
+                        Some(_) => (),
+                        None => {
+                            if let Some(ref time_zone) = date_zone.0 {
+                                members.push(("timeZone", string(time_zone.clone())));
+                            }
+                            if date_zone.1 {
+                                members.push(("showWithoutTime", Json::Bool(true)));
+                            }
+                            zone = Some(date_zone);
+                        }
+                    }
+                }
+            }
+            "DTEND" => {
+                end = Some(property_date_time(property).ok_or_else(|| unsupported("DTEND"))?)
+            }
+            "DURATION" => {
+                let key = if is_task {
+                    "estimatedDuration"
+                } else {
+                    "duration"
+                };
+                members.push((key, Json::String(value.into())));
+            }
+            "STATUS" => {
+                let key = if is_task { "progress" } else { "status" };
+                members.push((key, string(value.to_ascii_lowercase())));
+            }
+            "CLASS" => {
+                let privacy = match value.to_ascii_uppercase().as_str() {
+                    "PRIVATE" => "private",
+                    "CONFIDENTIAL" => "secret",
+                    _ => "public",
+                };
+                members.push(("privacy", Json::String(privacy.into())));
+            }
+            "TRANSP" => {
+                let status = if value.eq_ignore_ascii_case("TRANSPARENT") {
+                    "free"
+                } else {
+                    "busy"
+                };
+                members.push(("freeBusyStatus", Json::String(status.into())));
+            }
+            "LOCATION" => {
+                let location = object(vec![("@type", string("Location")), ("name", text())]);
+                locations.push((Cow::Owned((locations.len() + 1).to_string()), location));
+            }
+            "CATEGORIES" => {
+                for keyword in split_text_values(value) {
+                    keywords.push((keyword, Json::Bool(true)));
+                }
+            }
+            "RRULE" => rules.push(recurrence_rule(value, start_zone)?),
+            "EXDATE" | "RDATE" => {
+                let (name, excluded) = if property.key.eq_ignore_ascii_case("EXDATE") {
+                    ("EXDATE", true)
+                } else {
+                    ("RDATE", false)
+                };
+                let tzid = parameter(property, "TZID");
+                for value in split_values(value) {
+                    let (start, end) = match value.find('/') {
+                        Some(index) => (&value[..index], Some(&value[index + 1..])),
+                        None => (&value[..], None),
+                    };
+                    let start = date_time(start, tzid.clone())
+                        .filter(|date_time| {
+                            date_time.time_zone.as_ref().map(String::as_str) == start_zone
+                        })
+                        .ok_or_else(|| unsupported(name))?;
+                    let patch = match end {
+                        _ if excluded => object(vec![("excluded", Json::Bool(true))]),
+                        Some(end) => {
+                            let duration = period_duration(&start, end, tzid.clone())
+                                .ok_or_else(|| unsupported(name))?;
+                            object(vec![("duration", string(duration))])
+                        }
+                        None => Json::Object(Vec::new()),
+                    };
+                    overrides.push((Cow::Owned(start.local), patch));
+                }
+            }
+            "ORGANIZER" | "ATTENDEE" => {
+                let is_organizer = property.key.eq_ignore_ascii_case("ORGANIZER");
+                if is_organizer {
+                    reply_to = Some(object(vec![("imip", Json::String(value.into()))]));
+                }
+                participant(&mut participants, property, is_organizer);
+            }
+            _ => (),
+        }
+    }
+
+    if let Some(end) = end {
+        // The duration is only exact if the end is in the time zone of the
+        // start.
+        let seconds = match (start, zone) {
+            (Some(start), Some((time_zone, is_date)))
+                if end.time_zone == time_zone && end.is_date == is_date =>
+            {
+                timestamp(&end.local)
+                    .and_then(|end| Some(end - timestamp(&start)?))
+                    .filter(|seconds| *seconds >= 0)
+            }
+            _ => None,
+        };
+        let seconds = seconds.ok_or_else(|| unsupported("DTEND"))?;
+        members.push(("duration", string(format_duration(seconds))));
+    }
+    if !keywords.is_empty() {
+        members.push(("keywords", Json::Object(keywords)));
+    }
+    if !locations.is_empty() {
+        members.push(("locations", Json::Object(locations)));
+    }
+    if !rules.is_empty() {
+        members.push(("recurrenceRules", Json::Array(rules)));
+    }
+    if !overrides.is_empty() {
+        members.push(("recurrenceOverrides", Json::Object(overrides)));
+    }
+    if let Some(reply_to) = reply_to {
+        members.push(("replyTo", reply_to));
+    }
+    if !participants.is_empty() {
+        let participants = participants
+            .into_iter()
+            .enumerate()
+            .map(|(index, (_, members))| {
+                (Cow::Owned((index + 1).to_string()), Json::Object(members))
+            })
+            .collect();
+        members.push(("participants", Json::Object(participants)));
+    }
+    if !alarms.is_empty() {
+        let alerts = alarms
+            .iter()
+            .enumerate()
+            .map(|(index, alarm)| Ok((Cow::Owned((index + 1).to_string()), alert(alarm)?)))
+            .collect::<Result<_, Error>>()?;
+        members.push(("alerts", Json::Object(alerts)));
+    }
+    Ok(object(members))
+}
+
+// The duration of a period which ends with a duration or a date-time in the
+// time zone of its start.
+fn period_duration(start: &DateTime, end: &str, tzid: Option<String>) -> Option<String> {
+    if end.starts_with('P') || end.starts_with('+') {
+        return Some(end.trim_start_matches('+').to_string());
+    }
+    let end = date_time(end, tzid).filter(|end| end.time_zone == start.time_zone)?;
+    let seconds = timestamp(&end.local)? - timestamp(&start.local)?;
+    if seconds < 0 {
+        None
+    } else {
+        Some(format_duration(seconds))
+    }
+}
+
+// Rule parts and the names of their JSCalendar properties
+const RULE_PARTS: [(&str, &str); 14] = [
+    ("FREQ", "frequency"),
+    ("INTERVAL", "interval"),
+    ("RSCALE", "rscale"),
+    ("SKIP", "skip"),
+    ("WKST", "firstDayOfWeek"),
+    ("BYDAY", "byDay"),
+    ("BYMONTHDAY", "byMonthDay"),
+    ("BYMONTH", "byMonth"),
+    ("BYYEARDAY", "byYearDay"),
+    ("BYWEEKNO", "byWeekNo"),
+    ("BYHOUR", "byHour"),
+    ("BYMINUTE", "byMinute"),
+    ("BYSECOND", "bySecond"),
+    ("BYSETPOS", "bySetPosition"),
+];
+
+// FREQ=YEARLY;BYDAY=-1SU -> {"@type":"RecurrenceRule","frequency":"yearly",
+// "byDay":[{"@type":"NDay","day":"su","nthOfPeriod":-1}]}
+// Parts with invalid numbers are skipped. UNTIL becomes a local date-time in
+// the time zone of the start, so a UTC date-time can only be converted if the
+// start is in UTC as well.
+fn recurrence_rule<'a>(value: &'a str, start_zone: Option<&str>) -> Result<Json<'a>, Error> {
+    let mut members = vec![(Cow::Borrowed("@type"), string("RecurrenceRule"))];
+    for part in value.split(';') {
+        let (key, value) = match part.find('=') {
+            Some(index) => (part[..index].to_ascii_uppercase(), &part[index + 1..]),
+            None => continue,
+        };
+        let json = match key.as_str() {
+            "FREQ" | "RSCALE" | "SKIP" | "WKST" => Some(string(value.to_ascii_lowercase())),
+            "INTERVAL" | "COUNT" => integer(value),
+            "UNTIL" => {
+                let until = date_time(value, None).ok_or_else(|| unsupported("RRULE"))?;
+                if until.time_zone.is_some() && !is_utc(start_zone) {
+                    return Err(unsupported("RRULE"));
+                }
+                Some(string(until.local))
+            }
+            "BYDAY" => value
+                .split(',')
+                .map(|day| {
+                    let (nth, day) = day.split_at(day.len().saturating_sub(2));
+                    let mut members = vec![
+                        ("@type", string("NDay")),
+                        ("day", string(day.to_ascii_lowercase())),
+                    ];
+                    if !nth.is_empty() {
+                        members.push(("nthOfPeriod", integer(nth)?));
+                    }
+                    Some(object(members))
+                })
+                .collect::<Option<_>>()
+                .map(Json::Array),
+            "BYMONTH" => Some(Json::Array(value.split(',').map(string).collect())),
+            _ => value
+                .split(',')
+                .map(integer)
+                .collect::<Option<_>>()
+                .map(Json::Array),
+        };
+        let json = match json {
+            Some(json) => json,
+            None => continue,
+        };
+        let name = match key.as_str() {
+            "COUNT" => "count",
+            "UNTIL" => "until",
+            key => match RULE_PARTS.iter().find(|(part, _)| *part == key) {
+                Some(&(_, name)) => name,
+                None => continue,
+            },
+        };
+        members.push((Cow::Borrowed(name), json));
+    }
+    Ok(Json::Object(members))
+}
+
+// Adds an organizer or attendee to the participants. An organizer who also
+// attends is merged into one participant.
+fn participant<'a>(
+    participants: &mut Vec<(String, Members<'a>)>,
+    property: &'a Property,
+    is_organizer: bool,
+) {
+    let address = property.value.to_ascii_lowercase();
+    let index = match participants.iter().position(|(other, _)| *other == address) {
+        Some(index) => index,
+        None => {
+            let members = vec![
+                (Cow::Borrowed("@type"), string("Participant")),
+                (
+                    Cow::Borrowed("sendTo"),
+                    object(vec![("imip", Json::String(property.value.as_ref().into()))]),
+                ),
+            ];
+            participants.push((address, members));
+            participants.len() - 1
+        }
+    };
+    let members = &mut participants[index].1;
+    if let Some(name) = parameter(property, "CN") {
+        if !members.iter().any(|(key, _)| key == "name") {
+            members.push((Cow::Borrowed("name"), string(name)));
+        }
+    }
+    let mut roles = Vec::new();
+    if is_organizer {
+        roles.push("owner");
+    } else {
+        match parameter(property, "ROLE")
+            .map(|role| role.to_ascii_uppercase())
+            .as_ref()
+            .map(String::as_str)
+        {
+            Some("CHAIR") => roles.extend(&["attendee", "chair"]),
+            Some("OPT-PARTICIPANT") => roles.extend(&["attendee", "optional"]),
+            Some("NON-PARTICIPANT") => roles.push("informational"),
+            _ => roles.push("attendee"),
+        }
+        if let Some(status) = parameter(property, "PARTSTAT") {
+            members.push((
+                Cow::Borrowed("participationStatus"),
+                string(status.to_ascii_lowercase()),
+            ));
+        }
+        if let Some(rsvp) = parameter(property, "RSVP") {
+            members.push((
+                Cow::Borrowed("expectReply"),
+                Json::Bool(rsvp.eq_ignore_ascii_case("TRUE")),
+            ));
+        }
+        if let Some(kind) = parameter(property, "CUTYPE") {
+            members.push((Cow::Borrowed("kind"), string(kind.to_ascii_lowercase())));
+        }
+    }
+    let roles = roles
+        .into_iter()
+        .map(|role| (Cow::Borrowed(role), Json::Bool(true)));
+    match members.iter_mut().find(|(key, _)| key == "roles") {
+        Some((_, Json::Object(existing))) => existing.extend(roles),
+        _ => members.push((Cow::Borrowed("roles"), Json::Object(roles.collect()))),
+    }
+}
+
+fn alert<'a>(alarm: &'a Alarm) -> Result<Json<'a>, Error> {
+    let mut action = "display";
+    let mut trigger = None;
+    for property in &alarm.0 {
+        if property.key.eq_ignore_ascii_case("ACTION") {
+            action = match property.value.to_ascii_uppercase().as_str() {
+                "DISPLAY" => "display",
+                "EMAIL" => "email",
+                _ => return Err(unsupported("ACTION")),
+            };
+        } else if property.key.eq_ignore_ascii_case("TRIGGER") {
+            let is_absolute = parameter(property, "VALUE")
+                .map_or(false, |value| value.eq_ignore_ascii_case("DATE-TIME"));
+            trigger = Some(if is_absolute {
+                let when = utc_date_time(&property.value).ok_or_else(|| unsupported("TRIGGER"))?;
+                object(vec![
+                    ("@type", string("AbsoluteTrigger")),
+                    ("when", string(when)),
+                ])
+            } else {
+                let mut members = vec![
+                    ("@type", string("OffsetTrigger")),
+                    ("offset", Json::String(property.value.as_ref().into())),
+                ];
+                if parameter(property, "RELATED")
+                    .map_or(false, |related| related.eq_ignore_ascii_case("END"))
+                {
+                    members.push(("relativeTo", string("end")));
+                }
+                object(members)
+            });
+        }
+    }
+    let mut members = vec![("@type", string("Alert"))];
+    members.extend(trigger.map(|trigger| ("trigger", trigger)));
+    members.push(("action", string(action)));
+    Ok(object(members))
+}
+
+fn parse<'a>(input: &'a str, object_type: &'static str) -> Result<Members<'a>, Error> {
+    let members = match json::parse(input) {
+        Ok(Json::Object(members)) => members,
+        Ok(_) => return Err(Error::InvalidStructure { expected: "object" }),
+        Err(offset) => return Err(Error::Syntax { offset }),
+    };
+    match get_str(&members, "@type") {
+        Some(value) if value == object_type => Ok(members),
+        _ => Err(Error::InvalidStructure {
+            expected: if object_type == "Task" {
+                "Task object"
+            } else {
+                "Event object"
+            },
+        }),
+    }
+}
+
+fn get<'b, 'a>(members: &'b [(Cow<'a, str>, Json<'a>)], key: &str) -> Option<&'b Json<'a>> {
+    members
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value)
+}
+
+fn get_str<'b>(members: &'b [(Cow<str>, Json)], key: &str) -> Option<&'b str> {
+    match get(members, key) {
+        Some(Json::String(value)) | Some(Json::Number(value)) => Some(&**value),
+        _ => None,
+    }
+}
+
+fn get_object<'b, 'a>(
+    members: &'b [(Cow<'a, str>, Json<'a>)],
+    key: &str,
+) -> &'b [(Cow<'a, str>, Json<'a>)] {
+    match get(members, key) {
+        Some(Json::Object(members)) => members,
+        _ => &[],
+    }
+}
+
+fn is_true(members: &[(Cow<str>, Json)], key: &str) -> bool {
+    match get(members, key) {
+        Some(Json::Bool(value)) => *value,
+        _ => false,
+    }
+}
+
+fn required(members: &[(Cow<str>, Json)]) -> Result<(String, String), Error> {
+    let uid = get_str(members, "uid").ok_or(Error::InvalidStructure { expected: "uid" })?;
+    let updated = get_str(members, "updated")
+        .and_then(|updated| ical_date_time(updated, false, Some("Etc/UTC")))
+        .ok_or(Error::InvalidStructure {
+            expected: "updated",
+        })?;
+    Ok((uid.to_string(), updated))
+}
+
+// 1997-07-14T13:30:00 -> 19970714T133000 (or 19970714 for dates), UTC
+// date-times end with Z.
+fn ical_date_time(local: &str, is_date: bool, time_zone: Option<&str>) -> Option<String> {
+    let digits: String = local
+        .trim_end_matches('Z')
+        .chars()
+        .filter(|&c| c != '-' && c != ':')
+        .collect();
+    if digits.len() != 15 || digits.as_bytes()[8] != b'T' {
+        return None;
+    }
+    if is_date {
+        return Some(digits[..8].to_string());
+    }
+    let is_utc = local.ends_with('Z') || time_zone == Some("Etc/UTC") || time_zone == Some("UTC");
+    Some(if is_utc { digits + "Z" } else { digits })
+}
+
+// Creates a date or date-time property in the time zone of the object.
+fn date_time_property(
+    key: &'static str,
+    local: &str,
+    is_date: bool,
+    time_zone: Option<&str>,
+) -> Option<Property<'static>> {
+    let value = ical_date_time(local, is_date, time_zone)?;
+    let mut property = Property::new(key, value);
+    if is_date {
+        property.add(Value::DATE);
+    } else if let Some(time_zone) = time_zone {
+        if !property.value.ends_with('Z') {
+            property.add(TzIDParam::new(time_zone.to_string()));
+        }
+    }
+    Some(property)
+}
+
+// Converts a JSCalendar object into the properties (without UID and DTSTAMP)
+// and alarms of an event or to-do.
+fn from_json(
+    members: &[(Cow<str>, Json)],
+    is_task: bool,
+) -> Result<(Vec<Property<'static>>, Vec<Alarm<'static>>), Error> {
+    let mut properties: Vec<Property<'static>> = Vec::new();
+    let time_zone = get_str(members, "timeZone");
+    let is_date = is_true(members, "showWithoutTime");
+    let text = |key: &str| get_str(members, key).map(|value| escape_text(value.to_string()));
+
+    if let Some(created) =
+        get_str(members, "created").and_then(|value| ical_date_time(value, false, Some("Etc/UTC")))
+    {
+        properties.push(Created::new(created).into());
+    }
+    if let Some(sequence) = get_integer(members, "sequence")? {
+        properties.push(Sequence::new(sequence).into());
+    }
+    if let Some(title) = text("title") {
+        properties.push(Summary::new(title).into());
+    }
+    if let Some(description) = text("description") {
+        properties.push(Description::new(description).into());
+    }
+    if let Some(start) = get_str(members, "start") {
+        properties.extend(date_time_property("DTSTART", start, is_date, time_zone));
+    }
+    if let Some(due) = get_str(members, "due") {
+        properties.extend(date_time_property("DUE", due, is_date, time_zone));
+    }
+    let duration = if is_task {
+        "estimatedDuration"
+    } else {
+        "duration"
+    };
+    if let Some(duration) = get_str(members, duration) {
+        properties.push(Duration::new(duration.to_string()).into());
+    }
+    let status = if is_task { "progress" } else { "status" };
+    if let Some(status) = get_str(members, status) {
+        properties.push(Status::new(status.to_ascii_uppercase()).into());
+    }
+    if let Some(percent) = get_integer(members, "percentComplete")? {
+        properties.push(PercentComplete::new(percent).into());
+    }
+    if let Some(priority) = get_integer(members, "priority")? {
+        properties.push(Priority::new(priority).into());
+    }
+    match get_str(members, "privacy") {
+        Some("private") => properties.push(Class::private().into()),
+        Some("secret") => properties.push(Class::confidential().into()),
+        Some(_) => properties.push(Class::public().into()),
+        None => (),
+    }
+    match get_str(members, "freeBusyStatus") {
+        Some("free") => properties.push(Transp::transparent().into()),
+        Some(_) => properties.push(Transp::opaque().into()),
+        None => (),
+    }
+    if let Some(color) = text("color") {
+        properties.push(Property::new("COLOR", color));
+    }
+    let keywords = get_object(members, "keywords");
+    if !keywords.is_empty() {
+        let keywords = keywords.iter().map(|(keyword, _)| keyword.to_string());
+        properties.extend(Categories::from_list(keywords).map(Property::from));
+    }
+    for (_, location) in get_object(members, "locations") {
+        if let Json::Object(location) = location {
+            if let Some(name) = get_str(location, "name") {
+                properties.push(Location::new(escape_text(name.to_string())).into());
+            }
+        }
+    }
+    if let Some(Json::Array(rules)) = get(members, "recurrenceRules") {
+        for rule in rules {
+            if let Json::Object(rule) = rule {
+                let rule = ical_recurrence_rule(rule, is_date, time_zone)?;
+                properties.push(RRule::new(rule).into());
+            }
+        }
+    }
+    // Periods need VALUE=PERIOD, so they are kept apart from the other dates.
+    let mut rdates = Vec::new();
+    let mut periods = Vec::new();
+    let mut exdates = Vec::new();
+    for (local, patch) in get_object(members, "recurrenceOverrides") {
+        let patch: &[_] = match patch {
+            Json::Object(patch) => patch,
+            _ => &[],
+        };
+        let date = date_time_property("RDATE", local, is_date, time_zone);
+        if is_true(patch, "excluded") {
+            exdates.extend(date);
+        } else if let Some(duration) = get_str(patch, "duration") {
+            let mut date = match date {
+                Some(ref date) if !is_date => date.clone(),
+                _ => return Err(unsupported("recurrenceOverrides")),
+            };
+            date.value = Cow::Owned(format!("{}/{}", date.value, duration));
+            date.add(Value::PERIOD);
+            periods.push(date);
+        } else {
+            rdates.extend(date);
+        }
+    }
+    for (key, dates) in &[("RDATE", rdates), ("RDATE", periods), ("EXDATE", exdates)] {
+        if let Some(first) = dates.first() {
+            let values = dates.iter().map(|date| date.value.to_string());
+            let mut property = Property::new(*key, values.collect::<Vec<_>>().join(","));
+            property.append(first.parameters.clone());
+            properties.push(property);
+        }
+    }
+    let reply_to = get_str(get_object(members, "replyTo"), "imip");
+    for (_, participant) in get_object(members, "participants") {
+        if let Json::Object(participant) = participant {
+            properties.extend(ical_participant(participant, reply_to));
+        }
+    }
+    let title = get_str(members, "title").unwrap_or("Reminder");
+    let alarms = get_object(members, "alerts")
+        .iter()
+        .filter_map(|(_, alert)| match alert {
+            Json::Object(alert) => ical_alarm(alert, title),
+            _ => None,
+        })
+        .collect();
+    Ok((properties, alarms))
+}
+
+// Integer members may be written as JSON numbers or strings.
+fn get_integer(members: &[(Cow<str>, Json)], key: &str) -> Result<Option<String>, Error> {
+    match get_str(members, key) {
+        Some(value) => integer_value(value).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn integer_value(value: &str) -> Result<String, Error> {
+    value
+        .parse::<i64>()
+        .map(|value| value.to_string())
+        .map_err(|_| Error::InvalidStructure {
+            expected: "integer",
+        })
+}
+
+// The until date-time is local to the time zone of the start. Only UTC and
+// floating date-times can be written without time zone definitions.
+fn ical_recurrence_rule(
+    rule: &[(Cow<str>, Json)],
+    is_date: bool,
+    time_zone: Option<&str>,
+) -> Result<String, Error> {
+    let mut parts = Vec::new();
+    let names = RULE_PARTS
+        .iter()
+        .take(1)
+        .chain(&[("UNTIL", "until"), ("COUNT", "count")])
+        .chain(RULE_PARTS.iter().skip(1));
+    for &(part, name) in names {
+        let value = match get(rule, name) {
+            Some(value) => value,
+            None => continue,
+        };
+        let values: Vec<String> = match value {
+            Json::Array(values) => {
+                let mut list = Vec::new();
+                for value in values {
+                    match value {
+                        Json::Object(day) => {
+                            let nth = get_integer(day, "nthOfPeriod")?.unwrap_or_default();
+                            if let Some(day) = get_str(day, "day") {
+                                list.push(format!("{}{}", nth, day.to_ascii_uppercase()));
+                            }
+                        }
+                        Json::String(value) if part == "BYMONTH" => list.push(value.to_string()),
+                        Json::String(value) | Json::Number(value) => {
+                            list.push(integer_value(value)?)
+                        }
+                        _ => (),
+                    }
+                }
+                list
+            }
+            Json::String(value) if part == "UNTIL" => {
+                if time_zone.is_some() && !is_utc(time_zone) {
+                    return Err(unsupported("until"));
+                }
+                ical_date_time(value, is_date, time_zone)
+                    .into_iter()
+                    .collect()
+            }
+            Json::String(value) if part == "INTERVAL" || part == "COUNT" => {
+                vec![integer_value(value)?]
+            }
+            Json::String(value) => vec![value.to_ascii_uppercase()],
+            Json::Number(value) => vec![integer_value(value)?],
+            _ => continue,
+        };
+        parts.push(format!("{}={}", part, values.join(",")));
+    }
+    Ok(parts.join(";"))
+}
+
+fn ical_participant(
+    participant: &[(Cow<str>, Json)],
+    reply_to: Option<&str>,
+) -> Vec<Property<'static>> {
+    let roles = get_object(participant, "roles");
+    let has_role = |role| is_true(roles, role);
+    let address = get_str(get_object(participant, "sendTo"), "imip")
+        .or_else(|| if has_role("owner") { reply_to } else { None })
+        .map(str::to_string)
+        .or_else(|| get_str(participant, "email").map(|email| format!("mailto:{}", email)));
+    let address = match address {
+        Some(address) => address,
+        None => return Vec::new(),
+    };
+    let name = get_str(participant, "name").map(str::to_string);
+    let mut properties = Vec::new();
+    if has_role("owner") {
+        let mut organizer = Organizer::new(address.clone());
+        if let Some(name) = name.clone() {
+            organizer.add(CN::new(name));
+        }
+        properties.push(organizer.into());
+    }
+    let is_attendee = ["attendee", "chair", "optional", "informational"]
+        .iter()
+        .any(|&role| has_role(role));
+    if is_attendee || !has_role("owner") {
+        let mut attendee = Attendee::new(address);
+        if let Some(name) = name {
+            attendee.add(CN::new(name));
+        }
+        if has_role("chair") {
+            attendee.add(Role::CHAIR);
+        } else if has_role("optional") {
+            attendee.add(Role::OPT_PARTICIPANT);
+        } else if has_role("informational") && !has_role("attendee") {
+            attendee.add(Role::NON_PARTICIPANT);
+        }
+        if let Some(status) = get_str(participant, "participationStatus") {
+            attendee.add(PartStat::new(status.to_ascii_uppercase()));
+        }
+        match get(participant, "expectReply") {
+            Some(Json::Bool(true)) => attendee.add(RSVP::True),
+            Some(Json::Bool(false)) => attendee.add(RSVP::False),
+            _ => (),
+        }
+        if let Some(kind) = get_str(participant, "kind") {
+            attendee.add(CUType::new(kind.to_ascii_uppercase()));
+        }
+        properties.push(attendee.into());
+    }
+    properties
+}
+
+fn ical_alarm(alert: &[(Cow<str>, Json)], title: &str) -> Option<Alarm<'static>> {
+    let trigger = get_object(alert, "trigger");
+    let trigger = match get_str(trigger, "@type") {
+        Some("AbsoluteTrigger") => {
+            let mut trigger = Trigger::new(ical_date_time(
+                get_str(trigger, "when")?,
+                false,
+                Some("Etc/UTC"),
+            )?);
+            trigger.add(Value::DATE_TIME);
+            trigger
+        }
+        _ => {
+            let mut offset = Trigger::new(get_str(trigger, "offset")?.to_string());
+            if get_str(trigger, "relativeTo") == Some("end") {
+                offset.add(Related::End);
+            }
+            offset
+        }
+    };
+    // Email alerts have no recipients which an EMAIL alarm requires.
+    let description = Description::new(escape_text(title.to_string()));
+    Some(Alarm::display(trigger, description))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        event_from_str, event_to_string, format_duration, timestamp, todo_from_str, todo_to_string,
+        Error,
+    };
+    use crate::parameters::{PartStat, Related, Role, TzIDParam, Value, CN, RSVP};
+    use crate::properties::{
+        Attendee, Categories, Description, DtEnd, DtStart, Due, Duration, ExDate, Location,
+        Organizer, PercentComplete, Priority, RDate, RRule, Status, Summary, Trigger,
+    };
+    use crate::{Alarm, Event, ToDo};
+
+    #[test]
+    fn durations() {
+        let start = timestamp("2020-02-28T10:00:00").unwrap();
+        assert_eq!(timestamp("1970-01-01T00:00:00"), Some(0));
+        assert_eq!(
+            format_duration(timestamp("2020-03-01T11:30:00").unwrap() - start),
+            "P2DT1H30M"
+        );
+        assert_eq!(format_duration(0), "PT0S");
+    }
+
+    #[test]
+    fn event() {
+        let mut event = Event::new("uid", "20200102T182304Z");
+        event.push(DtStart::new("20200102T100000Z"));
+        event.push(DtEnd::new("20200102T113000Z"));
+        event.push(Summary::new("Meeting\\, weekly"));
+        event.push(Location::new("Room 1"));
+        event.push(Categories::from_list(vec!["work", "meeting"]).unwrap());
+        event.push(RRule::new("FREQ=WEEKLY;COUNT=5;BYDAY=-1SU,MO"));
+        event.push(ExDate::new("20200109T100000Z"));
+        let mut organizer = Organizer::new("mailto:a@example.com");
+        organizer.add(CN::new("A"));
+        event.push(organizer);
+        let mut attendee = Attendee::new("mailto:a@example.com");
+        attendee.add(Role::CHAIR);
+        attendee.add(PartStat::ACCEPTED);
+        event.push(attendee);
+        let mut attendee = Attendee::new("mailto:b@example.com");
+        attendee.add(RSVP::True);
+        event.push(attendee);
+        let mut trigger = Trigger::new("-PT15M");
+        trigger.add(Related::End);
+        event.add_alarm(Alarm::display(trigger, Description::new("Meeting")));
+
+        let json = event_to_string(&event).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"@type":"Event","uid":"uid","updated":"2020-01-02T18:23:04Z","#,
+                r#""start":"2020-01-02T10:00:00","timeZone":"Etc/UTC","title":"Meeting, weekly","#,
+                r#""duration":"PT1H30M","keywords":{"work":true,"meeting":true},"#,
+                r#""locations":{"1":{"@type":"Location","name":"Room 1"}},"#,
+                r#""recurrenceRules":[{"@type":"RecurrenceRule","frequency":"weekly","count":5,"#,
+                r#""byDay":[{"@type":"NDay","day":"su","nthOfPeriod":-1},{"@type":"NDay","day":"mo"}]}],"#,
+                r#""recurrenceOverrides":{"2020-01-09T10:00:00":{"excluded":true}},"#,
+                r#""replyTo":{"imip":"mailto:a@example.com"},"#,
+                r#""participants":{"1":{"@type":"Participant","sendTo":{"imip":"mailto:a@example.com"},"#,
+                r#""name":"A","roles":{"owner":true,"attendee":true,"chair":true},"participationStatus":"accepted"},"#,
+                r#""2":{"@type":"Participant","sendTo":{"imip":"mailto:b@example.com"},"expectReply":true,"roles":{"attendee":true}}},"#,
+                r#""alerts":{"1":{"@type":"Alert","trigger":{"@type":"OffsetTrigger","offset":"-PT15M","relativeTo":"end"},"action":"display"}}}"#
+            )
+        );
+
+        let event = event_from_str(&json).unwrap();
+        let ics = event.to_string();
+        for line in &[
+            "DTSTART:20200102T100000Z\r\n",
+            "DURATION:PT1H30M\r\n",
+            "SUMMARY:Meeting\\, weekly\r\n",
+            "CATEGORIES:work,meeting\r\n",
+            "RRULE:FREQ=WEEKLY;COUNT=5;BYDAY=-1SU,MO\r\n",
+            "EXDATE:20200109T100000Z\r\n",
+            "ORGANIZER;CN=A:mailto:a@example.com\r\n",
+            "ATTENDEE;CN=A;PARTSTAT=ACCEPTED;ROLE=CHAIR:mailto:a@example.com\r\n",
+            "ATTENDEE;RSVP=TRUE:mailto:b@example.com\r\n",
+            "TRIGGER;RELATED=END:-PT15M\r\n",
+        ] {
+            assert!(ics.contains(line), "{} missing in {}", line, ics);
+        }
+    }
+
+    #[test]
+    fn todo() {
+        let mut todo = ToDo::new("uid", "20200102T182304Z");
+        let mut due = Due::new("20200110");
+        due.add(Value::DATE);
+        todo.push(due);
+        todo.push(Duration::new("PT2H"));
+        todo.push(Status::in_process());
+        todo.push(PercentComplete::new("40"));
+
+        let json = todo_to_string(&todo).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"@type":"Task","uid":"uid","updated":"2020-01-02T18:23:04Z","#,
+                r#""due":"2020-01-10T00:00:00","showWithoutTime":true,"estimatedDuration":"PT2H","#,
+                r#""progress":"in-process","percentComplete":40}"#
+            )
+        );
+        assert_eq!(todo_from_str(&json), Ok(todo));
+        assert!(event_from_str(&json).is_err());
+    }
+
+    #[test]
+    fn time_zones() {
+        let mut todo = ToDo::new("uid", "20200102T182304Z");
+        let mut dtstart = DtStart::new("20200102T100000");
+        dtstart.add(TzIDParam::new("Europe/Berlin"));
+        todo.push(dtstart);
+        let mut due = Due::new("20200103T100000");
+        due.add(TzIDParam::new("Europe/Berlin"));
+        todo.push(due);
+        let json = todo_to_string(&todo).unwrap();
+        assert_eq!(json.matches("timeZone").count(), 1);
+        assert_eq!(todo_from_str(&json), Ok(todo));
+
+        let mut todo = ToDo::new("uid", "20200102T182304Z");
+        let mut dtstart = DtStart::new("20200102T100000");
+        dtstart.add(TzIDParam::new("Europe/Berlin"));
+        todo.push(dtstart);
+        let mut due = Due::new("20200103T100000");
+        due.add(TzIDParam::new("America/New_York"));
+        todo.push(due);
+        assert_eq!(
+            todo_to_string(&todo),
+            Err(Error::Unsupported { property: "DUE" })
+        );
+
+        let mut todo = ToDo::new("uid", "20200102T182304Z");
+        todo.push(DtStart::new("20200102T100000"));
+        let mut due = Due::new("20200103");
+        due.add(Value::DATE);
+        todo.push(due);
+        assert_eq!(
+            todo_to_string(&todo),
+            Err(Error::Unsupported { property: "DUE" })
+        );
+    }
+
+    #[test]
+    fn end_dates() {
+        let event = |end: DtEnd<'static>| {
+            let mut event = Event::new("uid", "20200102T182304Z");
+            let mut dtstart = DtStart::new("20200102T100000");
+            dtstart.add(TzIDParam::new("Europe/Berlin"));
+            event.push(dtstart);
+            event.push(end);
+            event_to_string(&event)
+        };
+        let mut dtend = DtEnd::new("20200102T113000");
+        dtend.add(TzIDParam::new("Europe/Berlin"));
+        assert!(event(dtend).unwrap().contains(r#""duration":"PT1H30M""#));
+
+        let unsupported = Err(Error::Unsupported { property: "DTEND" });
+        let mut dtend = DtEnd::new("20200102T113000");
+        dtend.add(TzIDParam::new("America/New_York"));
+        assert_eq!(event(dtend), unsupported);
+        assert_eq!(event(DtEnd::new("20200102T113000")), unsupported);
+        assert_eq!(event(DtEnd::new("20200102T113000Z")), unsupported);
+        let mut dtend = DtEnd::new("20200102T090000");
+        dtend.add(TzIDParam::new("Europe/Berlin"));
+        assert_eq!(event(dtend), unsupported);
+    }
+
+    #[test]
+    fn alarms() {
+        let mut event = Event::new("uid", "20200102T182304Z");
+        event.add_alarm(Alarm::audio(Trigger::new("-PT15M")));
+        assert_eq!(
+            event_to_string(&event),
+            Err(Error::Unsupported { property: "ACTION" })
+        );
+
+        let event = event_from_str(concat!(
+            r#"{"@type":"Event","uid":"uid","updated":"2020-01-02T18:23:04Z","title":"Meeting","#,
+            r#""alerts":{"1":{"@type":"Alert","trigger":{"@type":"OffsetTrigger","offset":"-PT15M"},"action":"email"}}}"#
+        ))
+        .unwrap();
+        let ics = event.to_string();
+        assert!(ics.contains("ACTION:DISPLAY\r\n"), "{}", ics);
+        assert!(!ics.contains("ACTION:EMAIL"), "{}", ics);
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        let mut event = Event::new("uid", "20200102T182304Z");
+        event.push(Priority::new("high"));
+        event.push(RRule::new("FREQ=DAILY;INTERVAL=x;BYHOUR=9,ten"));
+        let json = event_to_string(&event).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"@type":"Event","uid":"uid","updated":"2020-01-02T18:23:04Z","#,
+                r#""recurrenceRules":[{"@type":"RecurrenceRule","frequency":"daily"}]}"#
+            )
+        );
+        assert_eq!(
+            event_from_str(
+                r#"{"@type":"Event","uid":"uid","updated":"2020-01-02T18:23:04Z","priority":"high"}"#
+            ),
+            Err(Error::InvalidStructure {
+                expected: "integer"
+            })
+        );
+    }
+
+    #[test]
+    fn utc_dates() {
+        let mut event = Event::new("uid", "20200102T182304");
+        assert_eq!(
+            event_to_string(&event),
+            Err(Error::Unsupported {
+                property: "DTSTAMP"
+            })
+        );
+
+        event = Event::new("uid", "20200102T182304Z");
+        event.push(DtStart::new("20200102T100000Z"));
+        event.push(RRule::new("FREQ=DAILY;UNTIL=20200110T100000Z"));
+        let event = event_from_str(&event_to_string(&event).unwrap()).unwrap();
+        assert!(event
+            .to_string()
+            .contains("RRULE:FREQ=DAILY;UNTIL=20200110T100000Z\r\n"));
+
+        let mut event = Event::new("uid", "20200102T182304Z");
+        let mut dtstart = DtStart::new("20200102T100000");
+        dtstart.add(TzIDParam::new("Europe/Berlin"));
+        event.push(dtstart);
+        event.push(RRule::new("FREQ=DAILY;UNTIL=20200110T090000Z"));
+        assert_eq!(
+            event_to_string(&event),
+            Err(Error::Unsupported { property: "RRULE" })
+        );
+        assert_eq!(
+            event_from_str(concat!(
+                r#"{"@type":"Event","uid":"uid","updated":"2020-01-02T18:23:04Z","#,
+                r#""start":"2020-01-02T10:00:00","timeZone":"Europe/Berlin","#,
+                r#""recurrenceRules":[{"@type":"RecurrenceRule","frequency":"daily","until":"2020-01-10T10:00:00"}]}"#
+            )),
+            Err(Error::Unsupported { property: "until" })
+        );
+    }
+
+    #[test]
+    fn recurrence_dates() {
+        let mut event = Event::new("uid", "20200102T182304Z");
+        let mut dtstart = DtStart::new("20200102T100000");
+        dtstart.add(TzIDParam::new("Europe/Berlin"));
+        event.push(dtstart);
+        let mut rdate = RDate::new("20200105T100000/20200105T113000,20200107T100000/PT1H");
+        rdate.add(TzIDParam::new("Europe/Berlin"));
+        rdate.add(Value::PERIOD);
+        event.push(rdate);
+        let mut exdate = ExDate::new("20200109T100000");
+        exdate.add(TzIDParam::new("Europe/Berlin"));
+        event.push(exdate);
+
+        let json = event_to_string(&event).unwrap();
+        assert!(json.contains(concat!(
+            r#""recurrenceOverrides":{"2020-01-05T10:00:00":{"duration":"PT1H30M"},"#,
+            r#""2020-01-07T10:00:00":{"duration":"PT1H"},"2020-01-09T10:00:00":{"excluded":true}}"#
+        )));
+        let ics = event_from_str(&json)
+            .unwrap()
+            .to_string()
+            .replace("\r\n ", "");
+        for line in &[
+            "RDATE;TZID=Europe/Berlin;VALUE=PERIOD:20200105T100000/PT1H30M,20200107T100000/PT1H\r\n",
+            "EXDATE;TZID=Europe/Berlin:20200109T100000\r\n",
+        ] {
+            assert!(ics.contains(line), "{} missing in {}", line, ics);
+        }
+
+        event.push(ExDate::new("20200111T090000Z"));
+        assert_eq!(
+            event_to_string(&event),
+            Err(Error::Unsupported { property: "EXDATE" })
+        );
+    }
+
+    #[test]
+    fn syntax_error() {
+        assert_eq!(event_from_str("{"), Err(Error::Syntax { offset: 1 }));
+    }
+
+    #[test]
+    fn missing_uid() {
+        assert_eq!(
+            event_from_str(r#"{"@type":"Event","updated":"2020-01-02T18:23:04Z"}"#),
+            Err(Error::InvalidStructure { expected: "uid" })
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Minimal JSON representation, writer and parser used by the jCal format.
+use std::borrow::Cow;
+use std::fmt;
+
+/// A JSON value. Numbers are kept in their textual representation.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json<'a> {
+    Null,
+    Bool(bool),
+    Number(Cow<'a, str>),
+    String(Cow<'a, str>),
+    Array(Vec<Json<'a>>),
+    Object(Vec<(Cow<'a, str>, Json<'a>)>),
+}
+
+impl fmt::Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(members) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    let mut start = 0;
+    for (index, c) in value.char_indices() {
+        let escaped = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            '\u{8}' => "\\b",
+            '\u{c}' => "\\f",
+            c if c.is_control() && (c as u32) < 0x20 => "",
+            _ => continue,
+        };
+        f.write_str(&value[start..index])?;
+        if escaped.is_empty() {
+            write!(f, "\\u{:04x}", c as u32)?;
+        } else {
+            f.write_str(escaped)?;
+        }
+        start = index + c.len_utf8();
+    }
+    f.write_str(&value[start..])?;
+    write!(f, "\"")
+}
+
+// Nesting deeper than this is rejected to avoid overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Parses a JSON document. Strings without escape sequences are borrowed from
+/// the input. On error, the byte offset of the invalid input is returned.
+pub(crate) fn parse(input: &str) -> Result<Json<'_>, usize> {
+    let mut parser = Parser { input, offset: 0 };
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.offset == input.len() {
+        Ok(value)
+    } else {
+        Err(parser.offset)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.offset).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), usize> {
+        if self.input[self.offset..].starts_with(literal) {
+            self.offset += literal.len();
+            Ok(())
+        } else {
+            Err(self.offset)
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Json<'a>, usize> {
+        if depth > MAX_DEPTH {
+            return Err(self.offset);
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.expect("null").map(|_| Json::Null),
+            Some(b't') => self.expect("true").map(|_| Json::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => self.array(depth),
+            Some(b'{') => self.object(depth),
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            _ => Err(self.offset),
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Json<'a>, usize> {
+        self.offset += 1;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.offset += 1;
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b']') => {
+                    self.offset += 1;
+                    return Ok(Json::Array(values));
+                }
+                _ => return Err(self.offset),
+            }
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Json<'a>, usize> {
+        self.offset += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.offset += 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.offset);
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(":")?;
+            members.push((key, self.value(depth + 1)?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b'}') => {
+                    self.offset += 1;
+                    return Ok(Json::Object(members));
+                }
+                _ => return Err(self.offset),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json<'a>, usize> {
+        let start = self.offset;
+        let digits = |parser: &mut Self| {
+            let start = parser.offset;
+            while let Some(b'0'..=b'9') = parser.peek() {
+                parser.offset += 1;
+            }
+            if parser.offset == start {
+                Err(parser.offset)
+            } else {
+                Ok(())
+            }
+        };
+        if self.peek() == Some(b'-') {
+            self.offset += 1;
+        }
+        if self.peek() == Some(b'0') {
+            self.offset += 1;
+        } else {
+            digits(self)?;
+        }
+        if self.peek() == Some(b'.') {
+            self.offset += 1;
+            digits(self)?;
+        }
+        if let Some(b'e') | Some(b'E') = self.peek() {
+            self.offset += 1;
+            if let Some(b'+') | Some(b'-') = self.peek() {
+                self.offset += 1;
+            }
+            digits(self)?;
+        }
+        Ok(Json::Number(Cow::Borrowed(&self.input[start..self.offset])))
+    }
+
+    fn string(&mut self) -> Result<Cow<'a, str>, usize> {
+        self.offset += 1;
+        let start = self.offset;
+        let mut output: Option<String> = None;
+        loop {
+            let c = match self.input[self.offset..].chars().next() {
+                Some(c) => c,
+                None => return Err(self.offset),
+            };
+            match c {
+                '"' => {
+                    let value = match output {
+                        Some(output) => Cow::Owned(output),
+                        None => Cow::Borrowed(&self.input[start..self.offset]),
+                    };
+                    self.offset += 1;
+                    return Ok(value);
+                }
+                '\\' => {
+                    let output =
+                        output.get_or_insert_with(|| self.input[start..self.offset].to_string());
+                    self.offset += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.offset += 1;
+                            let c = self.unicode_escape()?;
+                            output.push(c);
+                            continue;
+                        }
+                        _ => return Err(self.offset),
+                    };
+                    output.push(escaped);
+                    self.offset += 1;
+                }
+                c if (c as u32) < 0x20 => return Err(self.offset),
+                c => {
+                    if let Some(output) = output.as_mut() {
+                        output.push(c);
+                    }
+                    self.offset += c.len_utf8();
+                }
+            }
+        }
+    }
+
+    // Parses the four hex digits after \u and a following low surrogate.
+    fn unicode_escape(&mut self) -> Result<char, usize> {
+        let high = self.hex()?;
+        if high < 0xD800 || high > 0xDFFF {
+            return std::char::from_u32(high).ok_or(self.offset);
+        }
+        if high > 0xDBFF {
+            return Err(self.offset);
+        }
+        self.expect("\\u")?;
+        let low = self.hex()?;
+        if low < 0xDC00 || low > 0xDFFF {
+            return Err(self.offset);
+        }
+        std::char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).ok_or(self.offset)
+    }
+
+    fn hex(&mut self) -> Result<u32, usize> {
+        let digits = self
+            .input
+            .get(self.offset..self.offset + 4)
+            .ok_or(self.offset)?;
+        let value = u32::from_str_radix(digits, 16).map_err(|_| self.offset)?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(self.offset);
+        }
+        self.offset += 4;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Json};
+
+    #[test]
+    fn write() {
+        let json = Json::Array(vec![
+            Json::String("a\"b\\c\nd\u{1}".into()),
+            Json::Object(vec![("x".into(), Json::Number("1.5".into()))]),
+            Json::Bool(true),
+        ]);
+        assert_eq!(json.to_string(), r#"["a\"b\\c\nd\u0001",{"x":1.5},true]"#);
+    }
+
+    #[test]
+    fn read() {
+        assert_eq!(
+            parse(r#" ["a\"\u00e9\ud83d\ude00", {"x": -1.5e3}, true, null, []] "#),
+            Ok(Json::Array(vec![
+                Json::String("a\"\u{e9}\u{1f600}".into()),
+                Json::Object(vec![("x".into(), Json::Number("-1.5e3".into()))]),
+                Json::Bool(true),
+                Json::Null,
+                Json::Array(Vec::new()),
+            ]))
+        );
+        assert_eq!(parse("[1,]"), Err(3));
+        assert_eq!(parse("01"), Err(1));
+        assert_eq!(parse("\"\\ud800\""), Err(7));
+        assert!(parse(&"[".repeat(1000)).is_err());
+    }
+}
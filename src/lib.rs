@@ -20,6 +20,16 @@
 //! # Features
 //! - `rfc7986` (enabled by default): adds properties from the newer
 //!   specification [RFC7986](https://tools.ietf.org/html/rfc7986)
+//! - `rfc6868`: encodes DQUOTE, newline and caret characters in parameter
+//!   values as specified in [RFC6868](https://tools.ietf.org/html/rfc6868)
+//! - `serde`: implements `Serialize` and `Deserialize` for the components and
+//!   properties. Deserialization borrows strings from the input where possible.
+//!   The `ser` module serializes arbitrary structs into components.
+//! - `derive`: re-exports the `IcsComponent` and `IcsProperty` derive macros
+//!   for custom components and properties from `ics-derive`.
+//! - `imip`: adds the `imip` module which renders scheduling messages as
+//!   MIME email messages as specified in
+//!   [RFC6047](https://tools.ietf.org/html/rfc6047)
 //!
 //! # Example
 //! ```
@@ -53,9 +63,21 @@ mod macros;
 pub mod components;
 mod contentline;
 mod ical;
+#[cfg(feature = "imip")]
+pub mod imip;
+pub mod itip;
+pub mod jcal;
+pub mod jscalendar;
+mod json;
 pub mod parameters;
 pub mod properties;
+#[cfg(feature = "serde")]
+pub mod ser;
 mod util;
+pub mod validation;
+pub mod values;
+pub mod xcal;
+mod xml;
 
 pub use ical::Alarm;
 pub use ical::Daylight;
@@ -68,3 +90,6 @@ pub use ical::TimeZone;
 pub use ical::ToDo;
 
 pub use util::escape_text;
+
+#[cfg(feature = "derive")]
+pub use ics_derive::{IcsComponent, IcsProperty};
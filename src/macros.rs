@@ -30,21 +30,6 @@ macro_rules! parameters {
     };
 }
 
-#[cfg(test)]
-mod test {
-    use crate::components::Parameters;
-    use std::collections::BTreeMap;
-
-    #[test]
-    fn parameters() {
-        let mut b_map: Parameters = BTreeMap::new();
-        b_map.insert("VALUE".into(), "BOOLEAN".into());
-        b_map.insert("CUTYPE".into(), "GROUP".into());
-        let param = parameters!("VALUE" => "BOOLEAN"; "CUTYPE" => "GROUP");
-        assert_eq!(b_map, param);
-    }
-}
-
 // Creation and conversion from builder types to Property
 macro_rules! property {
     ($type:ident, $name:expr) => {
@@ -149,23 +134,187 @@ macro_rules! parameter {
     };
 }
 
+// Creation and conversion from builder types to multi-valued Parameter
+macro_rules! parameter_list {
+    ($type:ident, $name:expr) => {
+        #[doc = "`"]
+        #[doc=$name]
+        #[doc = "` Parameter"]
+        ///
+        /// This parameter can have several values. Each value is quoted
+        /// individually.
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $type<'a> {
+            values: Vec<Cow<'a, str>>,
+        }
+
+        impl<'a> $type<'a> {
+            #[doc = "Creates a new `"]
+            #[doc=$name]
+            #[doc = "` Parameter with the given value."]
+            pub fn new<S>(value: S) -> Self
+            where
+                S: Into<Cow<'a, str>>,
+            {
+                Self {
+                    values: vec![value.into()],
+                }
+            }
+
+            #[doc = "Creates a new `"]
+            #[doc=$name]
+            #[doc = "` Parameter with the given values. A parameter without"]
+            /// values cannot be written, so `None` is returned if there are
+            /// no values.
+            pub fn from_list<I, S>(values: I) -> Option<Self>
+            where
+                I: IntoIterator<Item = S>,
+                S: Into<Cow<'a, str>>,
+            {
+                let values: Vec<_> = values.into_iter().map(Into::into).collect();
+                if values.is_empty() {
+                    None
+                } else {
+                    Some(Self { values })
+                }
+            }
+
+            /// Adds another value to the parameter.
+            pub fn push<S>(&mut self, value: S)
+            where
+                S: Into<Cow<'a, str>>,
+            {
+                self.values.push(value.into());
+            }
+
+            /// Returns the values of the parameter.
+            pub fn values(&self) -> &[Cow<'a, str>] {
+                &self.values
+            }
+        }
+
+        impl<'a> From<$type<'a>> for Parameter<'a> {
+            fn from(builder: $type<'a>) -> Self {
+                let values: Vec<_> = builder
+                    .values
+                    .iter()
+                    .map(|value| crate::util::quote_param_list_item(value))
+                    .collect();
+                Parameter {
+                    key: Cow::Borrowed($name),
+                    value: Cow::Owned(values.join(",")),
+                }
+            }
+        }
+    };
+}
+
+// Creation of properties from a list of values and splitting them back
+macro_rules! impl_list {
+    ($type:ident, TEXT) => {
+        impl<'a> $type<'a> {
+            /// Creates a new property from a list of values. Each value is
+            /// escaped individually and the values are joined with commas.
+            /// Returns `None` if there are no values.
+            pub fn from_list<I, S>(values: I) -> Option<Self>
+            where
+                I: IntoIterator<Item = S>,
+                S: Into<Cow<'a, str>>,
+            {
+                let values: Vec<_> = values
+                    .into_iter()
+                    .map(|value| crate::util::escape_text(value))
+                    .collect();
+                if values.is_empty() {
+                    None
+                } else {
+                    Some(Self::new(values.join(",")))
+                }
+            }
+
+            /// Returns the unescaped values of the property.
+            pub fn values(&self) -> Vec<Cow<'_, str>> {
+                crate::util::split_text_values(&self.value)
+            }
+        }
+    };
+    ($type:ident) => {
+        impl<'a> $type<'a> {
+            /// Creates a new property from a list of values. The values are
+            /// joined with commas. Returns `None` if there are no values.
+            pub fn from_list<I, S>(values: I) -> Option<Self>
+            where
+                I: IntoIterator<Item = S>,
+                S: Into<Cow<'a, str>>,
+            {
+                let values: Vec<Cow<'a, str>> = values.into_iter().map(Into::into).collect();
+                if values.is_empty() {
+                    None
+                } else {
+                    Some(Self::new(values.join(",")))
+                }
+            }
+
+            /// Returns the values of the property.
+            pub fn values(&self) -> Vec<Cow<'_, str>> {
+                crate::util::split_values(&self.value)
+            }
+        }
+    };
+}
+
+// Conversion from integers to properties with INTEGER value type
+macro_rules! impl_integer {
+    ($type:ident, $name:expr, $max:expr) => {
+        impl $type<'_> {
+            /// Returns the value as number if it is a valid value of the
+            /// property.
+            pub fn number(&self) -> Option<u32> {
+                self.value.parse().ok().filter(|&number| number <= $max)
+            }
+        }
+
+        impl TryFrom<u32> for $type<'_> {
+            type Error = validation::Error;
+
+            fn try_from(number: u32) -> Result<Self, Self::Error> {
+                if number <= $max {
+                    Ok(Self::new(number.to_string()))
+                } else {
+                    Err(validation::Error::InvalidValue {
+                        property: $name.to_string(),
+                        value_type: ValueType::Integer,
+                    })
+                }
+            }
+        }
+    };
+}
+
 macro_rules! impl_add_parameters {
     ($type:ident) => {
         impl<'a> $type<'a> {
-            /// Adds a parameter to the property.
+            /// Adds a parameter to the property. Values of multi-valued
+            /// parameters are added to the existing values, other parameters
+            /// are replaced.
             pub fn add<P>(&mut self, parameter: P)
             where
                 P: Into<Parameter<'a>>,
             {
-                let parameter = parameter.into();
-                self.parameters.insert(parameter.key, parameter.value);
+                crate::components::insert_parameter(&mut self.parameters, parameter.into());
             }
 
-            /// Adds several parameters at once to the property. For creating
-            /// several parameters at once, consult the documentation of
-            /// the [`parameters!`] macro.
-            pub fn append(&mut self, mut parameters: Parameters<'a>) {
-                self.parameters.append(&mut parameters);
+            /// Adds several parameters at once to the property. Values of
+            /// multi-valued parameters are merged like with `add`. For
+            /// creating several parameters at once, consult the documentation
+            /// of the [`parameters!`] macro.
+            pub fn append(&mut self, parameters: Parameters<'a>) {
+                for (key, value) in parameters {
+                    crate::components::insert_parameter(
+                        &mut self.parameters,
+                        Parameter { key, value },
+                    );
+                }
             }
         }
     };
@@ -184,3 +333,18 @@ macro_rules! impl_from_prop {
         }
     };
 }
+
+#[cfg(test)]
+mod test {
+    use crate::components::Parameters;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn parameters() {
+        let mut b_map: Parameters = BTreeMap::new();
+        b_map.insert("VALUE".into(), "BOOLEAN".into());
+        b_map.insert("CUTYPE".into(), "GROUP".into());
+        let param = parameters!("VALUE" => "BOOLEAN"; "CUTYPE" => "GROUP");
+        assert_eq!(b_map, param);
+    }
+}
@@ -19,18 +19,19 @@
 //! ```
 //! For more information on parameters, please refer to the specification [RFC5545 3.2. Property Parameters](https://tools.ietf.org/html/rfc5545#section-3.2) and [RFC7986 6. Property Parameters](https://tools.ietf.org/html/rfc7986#section-6).
 use crate::components::Parameter;
+use crate::values::MediaType;
 use std::borrow::Cow;
 
 parameter!(AltRep, "ALTREP");
 parameter!(CN, "CN");
 parameter!(CUType, "CUTYPE");
-parameter!(DelegatedFrom, "DELEGATED-FROM");
-parameter!(DelegatedTo, "DELEGATED-TO");
+parameter_list!(DelegatedFrom, "DELEGATED-FROM");
+parameter_list!(DelegatedTo, "DELEGATED-TO");
 parameter!(Dir, "DIR");
 parameter!(FmtType, "FMTTYPE");
 parameter!(FBType, "FBTYPE");
 parameter!(Language, "LANGUAGE");
-parameter!(Member, "MEMBER");
+parameter_list!(Member, "MEMBER");
 parameter!(PartStat, "PARTSTAT");
 parameter!(RelType, "RELTYPE");
 parameter!(Role, "ROLE");
@@ -65,6 +66,14 @@ impl CUType<'_> {
     };
 }
 
+impl FmtType<'_> {
+    /// Parses the value as media type. Returns `None` if the value is not a
+    /// valid media type.
+    pub fn media_type(&self) -> Option<MediaType<'_>> {
+        MediaType::parse(&self.value).ok()
+    }
+}
+
 impl FBType<'_> {
     /// The time interval is free for scheduling.
     pub const FREE: Self = Self {
@@ -20,10 +20,42 @@
 //! assert_eq!(Class::new("CONFIDENTIAL"), confidential);
 //! assert_eq!(Property::new("CLASS", "CONFIDENTIAL"), confidential.into());
 //! ```
+//!
+//! Properties like `CATEGORIES` or `EXDATE` can have several values which can
+//! be passed as list.
+//! ```
+//! use ics::components::Property;
+//! use ics::properties::Categories;
+//!
+//! let categories = Categories::from_list(vec!["MEETING", "PROJECT X, PHASE 2"]).unwrap();
+//! assert_eq!(categories.values(), vec!["MEETING", "PROJECT X, PHASE 2"]);
+//! assert_eq!(
+//!     Property::from(categories).to_string(),
+//!     "CATEGORIES:MEETING,PROJECT X\\, PHASE 2\r\n"
+//! );
+//! ```
+//!
+//! Properties with the value type `INTEGER` can be created from numbers.
+//! ```
+//! use ics::properties::{Priority, Sequence};
+//! use std::convert::TryFrom;
+//!
+//! assert_eq!(Priority::try_from(1), Ok(Priority::high()));
+//! assert!(Priority::try_from(10).is_err());
+//!
+//! let mut sequence = Sequence::try_from(1).unwrap();
+//! sequence.increment();
+//! assert_eq!(sequence.number(), Some(2));
+//! ```
 //! For more information on properties, please refer to the specification [RFC5545 3.7. Calendar Properties](https://tools.ietf.org/html/rfc5545#section-3.7) and [RFC7986 5. Properties](https://tools.ietf.org/html/rfc7986#section-5).
-use crate::components::{Parameter, Parameters, Property};
+use crate::components::{find_parameter, Parameter, Parameters, Property};
+use crate::parameters::FmtType;
+use crate::util;
+use crate::validation::{self, ValueType};
+use crate::values::{CalAddress, RequestStatusValue, UtcOffset};
 use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::convert::TryFrom;
 
 property!(CalScale, "CALSCALE");
 property!(Method, "METHOD");
@@ -72,6 +104,17 @@ property!(LastModified, "LAST-MODIFIED");
 property!(Sequence, "SEQUENCE");
 property!(RequestStatus, "REQUEST-STATUS");
 
+impl_list!(Categories, TEXT);
+impl_list!(Resources, TEXT);
+impl_list!(ExDate);
+impl_list!(RDate);
+impl_list!(FreeBusyTime);
+
+impl_integer!(PercentComplete, "PERCENT-COMPLETE", 100);
+impl_integer!(Priority, "PRIORITY", 9);
+impl_integer!(Repeat, "REPEAT", i32::max_value() as u32);
+impl_integer!(Sequence, "SEQUENCE", i32::max_value() as u32);
+
 impl Class<'_> {
     /// Specifies the access classification as public for a component (default value).
     pub fn public() -> Self {
@@ -158,6 +201,261 @@ impl Action<'_> {
     }
 }
 
+impl<'a> Attach<'a> {
+    /// Creates a new `ATTACH` Property from binary data and its media type
+    /// (e.g. `image/png`). The data is encoded with the `BASE64` encoding
+    /// which is why the `ENCODING`, `VALUE` and `FMTTYPE` parameters are also
+    /// added.
+    ///
+    /// # Example
+    /// ```
+    /// use ics::components::Property;
+    /// use ics::properties::Attach;
+    ///
+    /// let attach = Attach::from_bytes(b"Hello", "text/plain");
+    /// assert_eq!(attach.decode(), Some(b"Hello".to_vec()));
+    /// assert_eq!(
+    ///     Property::from(attach).to_string(),
+    ///     "ATTACH;ENCODING=BASE64;FMTTYPE=text/plain;VALUE=BINARY:SGVsbG8=\r\n"
+    /// );
+    /// ```
+    pub fn from_bytes<S>(data: &[u8], media_type: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let mut attach = Self::new(util::encode_base64(data));
+        attach.append(parameters!("ENCODING" => "BASE64"; "VALUE" => "BINARY"));
+        attach.add(FmtType::new(media_type));
+        attach
+    }
+
+    /// Decodes the value if it is binary data with the `BASE64` encoding.
+    /// Otherwise `None` is returned.
+    pub fn decode(&self) -> Option<Vec<u8>> {
+        decode_binary(&self.value, &self.parameters)
+    }
+}
+
+// Decodes the value of a property with `ENCODING=BASE64` and `VALUE=BINARY`.
+fn decode_binary(value: &str, parameters: &Parameters) -> Option<Vec<u8>> {
+    let is_base64 = find_parameter(parameters, "ENCODING")
+        .map_or(false, |encoding| encoding.eq_ignore_ascii_case("BASE64"));
+    let is_binary = find_parameter(parameters, "VALUE")
+        .map_or(false, |value| value.eq_ignore_ascii_case("BINARY"));
+    if is_base64 && is_binary {
+        util::decode_base64(value)
+    } else {
+        None
+    }
+}
+
+impl RequestStatus<'_> {
+    /// Parses the value into status code, description and data. Returns
+    /// `None` if the value is not valid.
+    pub fn status(&self) -> Option<RequestStatusValue<'_>> {
+        RequestStatusValue::parse(&self.value).ok()
+    }
+}
+
+impl TzOffsetFrom<'_> {
+    /// Parses the value as offset. Returns `None` if the value is not valid.
+    pub fn offset(&self) -> Option<UtcOffset> {
+        UtcOffset::parse(&self.value).ok()
+    }
+}
+
+impl TzOffsetTo<'_> {
+    /// Parses the value as offset. Returns `None` if the value is not valid.
+    pub fn offset(&self) -> Option<UtcOffset> {
+        UtcOffset::parse(&self.value).ok()
+    }
+}
+
+impl Attendee<'_> {
+    /// Creates a new `ATTENDEE` Property with a `mailto` URI from the given
+    /// email address. Returns an error if the email address is not valid.
+    pub fn mailto(email: &str) -> Result<Self, validation::Error> {
+        CalAddress::mailto(email).map(Self::new)
+    }
+}
+
+impl Organizer<'_> {
+    /// Creates a new `ORGANIZER` Property with a `mailto` URI from the given
+    /// email address. Returns an error if the email address is not valid.
+    pub fn mailto(email: &str) -> Result<Self, validation::Error> {
+        CalAddress::mailto(email).map(Self::new)
+    }
+}
+
+impl Priority<'_> {
+    /// Specifies the highest priority in a three-level priority scheme.
+    pub fn high() -> Self {
+        Self::new("1")
+    }
+
+    /// Specifies the medium priority in a three-level priority scheme.
+    pub fn medium() -> Self {
+        Self::new("5")
+    }
+
+    /// Specifies the lowest priority in a three-level priority scheme.
+    pub fn low() -> Self {
+        Self::new("9")
+    }
+
+    /// Specifies an undefined priority (default value).
+    pub fn undefined() -> Self {
+        Self::new("0")
+    }
+}
+
+impl Sequence<'_> {
+    /// Increments the revision sequence number. This should be done every time
+    /// the organizer makes a significant revision to the calendar component.
+    /// An invalid value is reset to 1.
+    pub fn increment(&mut self) {
+        let number = self.number().map_or(1, |number| {
+            number.saturating_add(1).min(i32::max_value() as u32)
+        });
+        self.value = Cow::Owned(number.to_string());
+    }
+}
+
+impl Geo<'_> {
+    /// Creates a new `GEO` Property from latitude and longitude in decimal
+    /// degrees. The values are written with six decimals.
+    ///
+    /// Returns an error if the latitude is not between -90 and 90 or the
+    /// longitude is not between -180 and 180 degrees.
+    ///
+    /// # Example
+    /// ```
+    /// use ics::properties::Geo;
+    ///
+    /// let geo = Geo::from_coordinates(37.386013, -122.082932).unwrap();
+    /// assert_eq!(geo, Geo::new("37.386013;-122.082932"));
+    /// assert_eq!(geo.coordinates(), Some((37.386013, -122.082932)));
+    /// assert!(Geo::from_coordinates(91.0, 0.0).is_err());
+    /// assert_eq!(Geo::from_coordinates(-1e-7, 0.0).unwrap(), Geo::new("0.000000;0.000000"));
+    /// ```
+    pub fn from_coordinates(latitude: f64, longitude: f64) -> Result<Self, validation::Error> {
+        if !is_coordinate(latitude, 90.0) || !is_coordinate(longitude, 180.0) {
+            return Err(invalid_geo());
+        }
+        Ok(Self::new(format!(
+            "{:.6};{:.6}",
+            round_coordinate(latitude),
+            round_coordinate(longitude)
+        )))
+    }
+
+    /// Creates a new `GEO` Property from a `geo` URI as specified in
+    /// [RFC5870](https://tools.ietf.org/html/rfc5870). The altitude and the
+    /// uncertainty are ignored. Only the default coordinate reference system
+    /// `wgs84` is supported.
+    ///
+    /// # Example
+    /// ```
+    /// use ics::properties::Geo;
+    ///
+    /// let geo = Geo::from_geo_uri("geo:48.198634,16.371648;u=40").unwrap();
+    /// assert_eq!(geo, Geo::new("48.198634;16.371648"));
+    /// ```
+    pub fn from_geo_uri(uri: &str) -> Result<Self, validation::Error> {
+        let scheme = uri.get(..4).ok_or_else(invalid_geo)?;
+        if !scheme.eq_ignore_ascii_case("geo:") {
+            return Err(invalid_geo());
+        }
+        let mut parts = uri[4..].split(';');
+        let coordinates = parts.next().unwrap_or("");
+        for parameter in parts {
+            let mut pair = parameter.splitn(2, '=');
+            let key = pair.next().unwrap_or("");
+            let value = pair.next().unwrap_or("");
+            if key.eq_ignore_ascii_case("crs") && !value.eq_ignore_ascii_case("wgs84") {
+                return Err(invalid_geo());
+            }
+        }
+        let mut coordinates = coordinates.split(',');
+        match (coordinates.next(), coordinates.next(), coordinates.next()) {
+            (Some(latitude), Some(longitude), altitude)
+                if altitude.map_or(true, validation::is_float) =>
+            {
+                Self::from_coordinates(parse_float(latitude)?, parse_float(longitude)?)
+            }
+            _ => Err(invalid_geo()),
+        }
+    }
+
+    /// Returns latitude and longitude in decimal degrees if the value is valid.
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        let mut parts = self.value.split(';');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(latitude), Some(longitude), None) => {
+                let latitude = parse_float(latitude).ok()?;
+                let longitude = parse_float(longitude).ok()?;
+                if is_coordinate(latitude, 90.0) && is_coordinate(longitude, 180.0) {
+                    Some((latitude, longitude))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the latitude in decimal degrees if the value is valid.
+    pub fn latitude(&self) -> Option<f64> {
+        self.coordinates().map(|(latitude, _)| latitude)
+    }
+
+    /// Returns the longitude in decimal degrees if the value is valid.
+    pub fn longitude(&self) -> Option<f64> {
+        self.coordinates().map(|(_, longitude)| longitude)
+    }
+
+    /// Converts the value into a `geo` URI as specified in
+    /// [RFC5870](https://tools.ietf.org/html/rfc5870) if the value is valid.
+    ///
+    /// # Example
+    /// ```
+    /// use ics::properties::Geo;
+    ///
+    /// let geo = Geo::new("37.386013;-122.082932");
+    /// assert_eq!(geo.to_geo_uri(), Some("geo:37.386013,-122.082932".to_string()));
+    /// ```
+    pub fn to_geo_uri(&self) -> Option<String> {
+        self.coordinates()
+            .map(|_| format!("geo:{}", self.value.replacen(';', ",", 1)))
+    }
+}
+
+fn is_coordinate(value: f64, limit: f64) -> bool {
+    value >= -limit && value <= limit
+}
+
+// Rounds a coordinate to the 6 decimal places that are written. Adding zero
+// turns a negative zero (also from rounding small negative values) into a
+// positive zero.
+fn round_coordinate(value: f64) -> f64 {
+    (value * 1e6).round() / 1e6 + 0.0
+}
+
+fn parse_float(input: &str) -> Result<f64, validation::Error> {
+    if validation::is_float(input) {
+        input.parse().map_err(|_| invalid_geo())
+    } else {
+        Err(invalid_geo())
+    }
+}
+
+fn invalid_geo() -> validation::Error {
+    validation::Error::InvalidValue {
+        property: "GEO".to_string(),
+        value_type: ValueType::Float,
+    }
+}
+
 impl Default for Class<'_> {
     fn default() -> Self {
         Self::public()
@@ -181,10 +479,7 @@ impl Default for CalScale<'_> {
 
 impl Default for Priority<'_> {
     fn default() -> Self {
-        Self {
-            value: Cow::Borrowed("0"),
-            parameters: BTreeMap::new(),
-        }
+        Self::undefined()
     }
 }
 
@@ -212,6 +507,8 @@ pub use self::rfc7986::*;
 #[cfg(feature = "rfc7986")]
 mod rfc7986 {
     use crate::components::{Parameter, Parameters, Property};
+    use crate::parameters::FmtType;
+    use crate::util;
     use std::borrow::Cow;
     use std::collections::BTreeMap;
     property!(Name, "NAME");
@@ -259,6 +556,27 @@ mod rfc7986 {
         }
     }
 
+    impl<'a> Image<'a> {
+        /// Creates a new `IMAGE` Property from binary data and its media type
+        /// (e.g. `image/png`). The data is encoded with the `BASE64` encoding
+        /// which is why the `ENCODING`, `VALUE` and `FMTTYPE` parameters are
+        /// also added.
+        pub fn from_bytes<S>(data: &[u8], media_type: S) -> Self
+        where
+            S: Into<Cow<'a, str>>,
+        {
+            let mut image = Self::binary(util::encode_base64(data));
+            image.add(FmtType::new(media_type));
+            image
+        }
+
+        /// Decodes the value if it is binary data with the `BASE64` encoding.
+        /// Otherwise `None` is returned.
+        pub fn decode(&self) -> Option<Vec<u8>> {
+            super::decode_binary(&self.value, &self.parameters)
+        }
+    }
+
     impl_add_parameters!(Image);
 
     impl_from_prop!(Image, "IMAGE");
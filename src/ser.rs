@@ -0,0 +1,1162 @@
+//! Serializes Rust structs into components with `serde`.
+//!
+//! This module requires the `serde` feature. Every field of a struct (or
+//! entry of a map) becomes a property whose name is the field name in upper
+//! case with underscores replaced by hyphens, e.g. `recurrence_id` becomes
+//! `RECURRENCE-ID`. `#[serde(rename = "...")]` can be used to choose another
+//! name.
+//!
+//! - Strings are escaped if the property has the value type `TEXT` or is
+//!   unknown. Booleans, integers, floats and bytes (as `BASE64`) add the
+//!   `VALUE` parameter if the property does not have this value type by
+//!   default.
+//! - `None` and `()` skip the property, sequences repeat it.
+//! - Unit enum variants are written as their name in upper case with hyphens
+//!   between words, e.g. `NeedsAction` becomes `NEEDS-ACTION`. Newtype variants
+//!   named after a value type, e.g. `Date(String)`, set the `VALUE` parameter.
+//! - A nested struct or map is a property with parameters. Its `value` field is
+//!   the value of the property, the `value_type` field sets the `VALUE`
+//!   parameter and all other fields are parameters. Sequences can be used for
+//!   multi-valued parameters like `MEMBER`.
+//! - Fields named after a component, e.g. `valarm`, contain sub-components.
+//!
+//! # Example
+//! ```
+//! use ics::ser;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! enum PartStat {
+//!     Accepted,
+//!     NeedsAction,
+//! }
+//!
+//! #[derive(Serialize)]
+//! struct Attendee {
+//!     value: &'static str,
+//!     partstat: PartStat,
+//!     rsvp: bool,
+//! }
+//!
+//! #[derive(Serialize)]
+//! struct Meeting {
+//!     uid: &'static str,
+//!     dtstamp: &'static str,
+//!     #[serde(rename = "dtstart")]
+//!     start: &'static str,
+//!     summary: &'static str,
+//!     #[serde(rename = "attendee")]
+//!     attendees: Vec<Attendee>,
+//! }
+//!
+//! let meeting = Meeting {
+//!     uid: "19970610T172345Z-AF23B2@example.com",
+//!     dtstamp: "19970610T172345Z",
+//!     start: "19970714T170000Z",
+//!     summary: "Bastille Day Party, Paris",
+//!     attendees: vec![Attendee {
+//!         value: "mailto:jsmith@example.com",
+//!         partstat: PartStat::NeedsAction,
+//!         rsvp: true,
+//!     }],
+//! };
+//! assert_eq!(
+//!     ser::to_string("VEVENT", &meeting).unwrap(),
+//!     "BEGIN:VEVENT\r\n\
+//!      UID:19970610T172345Z-AF23B2@example.com\r\n\
+//!      DTSTAMP:19970610T172345Z\r\n\
+//!      DTSTART:19970714T170000Z\r\n\
+//!      SUMMARY:Bastille Day Party\\, Paris\r\n\
+//!      ATTENDEE;PARTSTAT=NEEDS-ACTION;RSVP=TRUE:mailto:jsmith@example.com\r\n\
+//!      END:VEVENT\r\n"
+//! );
+//! ```
+use crate::components::{
+    insert_parameter, is_multi_valued, Component, Parameter, Parameters, Property,
+};
+use crate::util::{encode_base64, escape_text};
+use crate::validation::{self, ValueType};
+use serde::ser::{self, Impossible, Serialize};
+use std::borrow::Cow;
+use std::error;
+use std::fmt;
+
+/// Serializes a struct or map into a component with the given name.
+///
+/// The component can be added to an `ICalendar` with `add_component`.
+pub fn to_component<T>(name: &str, value: &T) -> Result<Component<'static>, Error>
+where
+    T: ?Sized + Serialize,
+{
+    let mut root = Component::new("");
+    value.serialize(FieldSerializer {
+        name: name.to_string(),
+        parent: &mut root,
+        kind: Kind::Component,
+        nested: true,
+    })?;
+    root.subcomponents.pop().ok_or(Error::Unsupported {
+        expected: "a struct or map",
+    })
+}
+
+/// Serializes a struct or map into the textual representation of a component
+/// with the given name.
+pub fn to_string<T>(name: &str, value: &T) -> Result<String, Error>
+where
+    T: ?Sized + Serialize,
+{
+    to_component(name, value).map(|component| component.to_string())
+}
+
+/// The error type for serializing components.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// An error reported by a `Serialize` implementation.
+    Custom(String),
+    /// The value cannot be represented at its position.
+    Unsupported {
+        /// Description of the expected value, e.g. `a struct or map`
+        expected: &'static str,
+    },
+    /// The value cannot be written as value type of the property.
+    InvalidValue(validation::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Custom(message) => write!(f, "{}", message),
+            Error::Unsupported { expected } => {
+                write!(f, "unsupported value: expected {}", expected)
+            }
+            Error::InvalidValue(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Error::Custom(message.to_string())
+    }
+}
+
+const COMPONENTS: [&str; 8] = [
+    "VEVENT",
+    "VTODO",
+    "VJOURNAL",
+    "VFREEBUSY",
+    "VTIMEZONE",
+    "VALARM",
+    "STANDARD",
+    "DAYLIGHT",
+];
+
+// Converts field and variant names like `recurrence_id` or `NeedsAction` into
+// `RECURRENCE-ID` and `NEEDS-ACTION`.
+fn to_name(field: &str) -> String {
+    let mut name = String::with_capacity(field.len() + 2);
+    let mut previous_lowercase = false;
+    for c in field.chars() {
+        if c == '_' {
+            name.push('-');
+            previous_lowercase = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lowercase {
+            name.push('-');
+        }
+        previous_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        name.extend(c.to_uppercase());
+    }
+    name
+}
+
+fn unsupported(expected: &'static str) -> Error {
+    Error::Unsupported { expected }
+}
+
+fn invalid_value(property: &str, value_type: ValueType) -> Error {
+    Error::InvalidValue(validation::Error::InvalidValue {
+        property: property.to_string(),
+        value_type,
+    })
+}
+
+// A property or parameter value and the value type it was serialized from.
+// Text has no value type because it is valid for every property.
+struct Scalar {
+    value: String,
+    value_type: Option<ValueType>,
+}
+
+impl Scalar {
+    fn text(value: String) -> Self {
+        Scalar {
+            value,
+            value_type: None,
+        }
+    }
+
+    fn typed(value: String, value_type: ValueType) -> Self {
+        Scalar {
+            value,
+            value_type: Some(value_type),
+        }
+    }
+
+    fn into_property(self, name: String, parameters: Parameters<'static>) -> Property<'static> {
+        let mut property = Property::new(name, self.value);
+        property.append(parameters);
+        if let Some(value_type) = self.value_type {
+            // The value type is only added if the property allows it, otherwise
+            // the value is written as the default value type.
+            let add = match validation::value_types(&property.key) {
+                Some(value_types) => value_types[1..].contains(&value_type),
+                None => true,
+            };
+            if add && !property.parameters.contains_key("VALUE") {
+                property.add(Parameter::new("VALUE", value_type.name()));
+            }
+        }
+        match validation::value_type(&property) {
+            Some(ValueType::Binary) => property.add(Parameter::new("ENCODING", "BASE64")),
+            Some(ValueType::Text) | None => {
+                if let Cow::Owned(value) = escape_text(&*property.value) {
+                    property.value = Cow::Owned(value);
+                }
+            }
+            Some(_) => (),
+        }
+        property
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Property,
+    Component,
+}
+
+// Implements the scalar methods of a serializer by serializing the value with
+// `ScalarSerializer` and passing the result to `self.scalar`.
+macro_rules! forward_scalars {
+    ($($method:ident($ty:ty)),*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<Self::Ok, Error> {
+                let scalar = ScalarSerializer { name: &self.name }.$method(value)?;
+                self.scalar(scalar)
+            }
+        )*
+    };
+}
+
+macro_rules! forward_all_scalars {
+    () => {
+        forward_scalars!(
+            serialize_bool(bool),
+            serialize_i8(i8),
+            serialize_i16(i16),
+            serialize_i32(i32),
+            serialize_i64(i64),
+            serialize_u8(u8),
+            serialize_u16(u16),
+            serialize_u32(u32),
+            serialize_u64(u64),
+            serialize_f32(f32),
+            serialize_f64(f64),
+            serialize_char(char),
+            serialize_str(&str),
+            serialize_bytes(&[u8])
+        );
+    };
+}
+
+// Serializes a field of a component into properties or sub-components.
+struct FieldSerializer<'c> {
+    name: String,
+    parent: &'c mut Component<'static>,
+    kind: Kind,
+    // Sequences cannot be nested.
+    nested: bool,
+}
+
+impl<'c> FieldSerializer<'c> {
+    fn scalar(self, scalar: Scalar) -> Result<(), Error> {
+        if self.kind == Kind::Component {
+            return Err(unsupported("a struct or map"));
+        }
+        let property = scalar.into_property(self.name, Parameters::new());
+        self.parent.add_property(property);
+        Ok(())
+    }
+
+    fn fields(self) -> Result<Fields<'c>, Error> {
+        let target = match self.kind {
+            Kind::Property => Target::Property {
+                value: None,
+                parameters: Parameters::new(),
+            },
+            Kind::Component => Target::Component(Component::new(self.name.clone())),
+        };
+        Ok(Fields {
+            name: self.name,
+            parent: self.parent,
+            target,
+            key: None,
+        })
+    }
+
+    fn repeated(self) -> Result<Repeated<'c>, Error> {
+        if self.nested {
+            return Err(unsupported("a single value"));
+        }
+        Ok(Repeated {
+            name: self.name,
+            parent: self.parent,
+            kind: self.kind,
+        })
+    }
+}
+
+impl<'c> ser::Serializer for FieldSerializer<'c> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Repeated<'c>;
+    type SerializeTuple = Repeated<'c>;
+    type SerializeTupleStruct = Repeated<'c>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Fields<'c>;
+    type SerializeStruct = Fields<'c>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    forward_all_scalars!();
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        let scalar =
+            ScalarSerializer { name: &self.name }.serialize_unit_variant(name, index, variant)?;
+        self.scalar(scalar)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let scalar = ScalarSerializer { name: &self.name }
+            .serialize_newtype_variant(name, index, variant, value)?;
+        self.scalar(scalar)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Repeated<'c>, Error> {
+        self.repeated()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Repeated<'c>, Error> {
+        self.repeated()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Repeated<'c>, Error> {
+        self.repeated()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(unsupported("a sequence without variant"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Fields<'c>, Error> {
+        self.fields()
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Fields<'c>, Error> {
+        self.fields()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(unsupported("a struct without variant"))
+    }
+}
+
+// Repeats a property or sub-component for every element of a sequence.
+struct Repeated<'c> {
+    name: String,
+    parent: &'c mut Component<'static>,
+    kind: Kind,
+}
+
+impl<'c> Repeated<'c> {
+    fn element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(FieldSerializer {
+            name: self.name.clone(),
+            parent: &mut *self.parent,
+            kind: self.kind,
+            nested: true,
+        })
+    }
+}
+
+impl<'c> ser::SerializeSeq for Repeated<'c> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'c> ser::SerializeTuple for Repeated<'c> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'c> ser::SerializeTupleStruct for Repeated<'c> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+// Collects the fields of a struct or map into a component or into the value
+// and parameters of a property.
+struct Fields<'c> {
+    name: String,
+    parent: &'c mut Component<'static>,
+    target: Target,
+    // Key of a map entry whose value is not serialized yet
+    key: Option<String>,
+}
+
+enum Target {
+    Property {
+        value: Option<Scalar>,
+        parameters: Parameters<'static>,
+    },
+    Component(Component<'static>),
+}
+
+impl<'c> Fields<'c> {
+    fn field<T>(&mut self, key: &str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let name = to_name(key);
+        match &mut self.target {
+            Target::Component(component) => {
+                let kind = if COMPONENTS.contains(&&*name) {
+                    Kind::Component
+                } else {
+                    Kind::Property
+                };
+                value.serialize(FieldSerializer {
+                    name,
+                    parent: component,
+                    kind,
+                    nested: false,
+                })
+            }
+            Target::Property {
+                value: property_value,
+                parameters,
+            } => {
+                if key == "value" {
+                    let scalar = value.serialize(ScalarSerializer { name: &self.name })?;
+                    *property_value = Some(scalar);
+                    return Ok(());
+                }
+                let name = if key == "value_type" {
+                    "VALUE".to_string()
+                } else {
+                    name
+                };
+                let values = value.serialize(ParameterSerializer { name: &name })?;
+                if values.len() > 1 && !is_multi_valued(&name) {
+                    return Err(unsupported("a single parameter value"));
+                }
+                for value in values {
+                    insert_parameter(parameters, Parameter::new(name.clone(), value));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn end(self) -> Result<(), Error> {
+        match self.target {
+            Target::Property { value, parameters } => {
+                let value = value.ok_or_else(|| unsupported("a `value` field"))?;
+                self.parent
+                    .add_property(value.into_property(self.name, parameters));
+            }
+            Target::Component(component) => self.parent.add_component(component),
+        }
+        Ok(())
+    }
+}
+
+impl<'c> ser::SerializeStruct for Fields<'c> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Fields::end(self)
+    }
+}
+
+impl<'c> ser::SerializeMap for Fields<'c> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = key.serialize(ScalarSerializer { name: &self.name })?;
+        self.key = Some(key.value);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self.key.take().ok_or_else(|| unsupported("a map key"))?;
+        self.field(&key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Fields::end(self)
+    }
+}
+
+// Serializes the values of a parameter. `None` returns no values and
+// sequences return several values.
+struct ParameterSerializer<'n> {
+    name: &'n str,
+}
+
+impl<'n> ParameterSerializer<'n> {
+    fn scalar(self, scalar: Scalar) -> Result<Vec<String>, Error> {
+        Ok(vec![scalar.value])
+    }
+
+    fn values(self) -> Result<ParameterValues<'n>, Error> {
+        Ok(ParameterValues {
+            name: self.name,
+            values: Vec::new(),
+        })
+    }
+}
+
+impl<'n> ser::Serializer for ParameterSerializer<'n> {
+    type Ok = Vec<String>;
+    type Error = Error;
+    type SerializeSeq = ParameterValues<'n>;
+    type SerializeTuple = ParameterValues<'n>;
+    type SerializeTupleStruct = ParameterValues<'n>;
+    type SerializeTupleVariant = Impossible<Vec<String>, Error>;
+    type SerializeMap = Impossible<Vec<String>, Error>;
+    type SerializeStruct = Impossible<Vec<String>, Error>;
+    type SerializeStructVariant = Impossible<Vec<String>, Error>;
+
+    forward_all_scalars!();
+
+    fn serialize_none(self) -> Result<Vec<String>, Error> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Vec<String>, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Vec<String>, Error> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Vec<String>, Error> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<Vec<String>, Error> {
+        let scalar =
+            ScalarSerializer { name: self.name }.serialize_unit_variant(name, index, variant)?;
+        self.scalar(scalar)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Vec<String>, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Vec<String>, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(unsupported("a parameter value"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<ParameterValues<'n>, Error> {
+        self.values()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<ParameterValues<'n>, Error> {
+        self.values()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<ParameterValues<'n>, Error> {
+        self.values()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(unsupported("a parameter value"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(unsupported("a parameter value"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(unsupported("a parameter value"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(unsupported("a parameter value"))
+    }
+}
+
+struct ParameterValues<'n> {
+    name: &'n str,
+    values: Vec<String>,
+}
+
+impl<'n> ParameterValues<'n> {
+    fn element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let scalar = value.serialize(ScalarSerializer { name: self.name })?;
+        self.values.push(scalar.value);
+        Ok(())
+    }
+}
+
+impl<'n> ser::SerializeSeq for ParameterValues<'n> {
+    type Ok = Vec<String>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Vec<String>, Error> {
+        Ok(self.values)
+    }
+}
+
+impl<'n> ser::SerializeTuple for ParameterValues<'n> {
+    type Ok = Vec<String>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Vec<String>, Error> {
+        Ok(self.values)
+    }
+}
+
+impl<'n> ser::SerializeTupleStruct for ParameterValues<'n> {
+    type Ok = Vec<String>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Vec<String>, Error> {
+        Ok(self.values)
+    }
+}
+
+// Serializes a single value. The name of the property or parameter is used for
+// error messages.
+struct ScalarSerializer<'n> {
+    name: &'n str,
+}
+
+impl<'n> ScalarSerializer<'n> {
+    fn integer(self, value: i64) -> Result<Scalar, Error> {
+        if value < i64::from(i32::min_value()) || value > i64::from(i32::max_value()) {
+            return Err(invalid_value(self.name, ValueType::Integer));
+        }
+        Ok(Scalar::typed(value.to_string(), ValueType::Integer))
+    }
+
+    fn float(self, finite: bool, zero: bool, value: String) -> Result<Scalar, Error> {
+        if !finite {
+            return Err(invalid_value(self.name, ValueType::Float));
+        }
+        // avoid writing -0
+        let value = if zero { "0".to_string() } else { value };
+        Ok(Scalar::typed(value, ValueType::Float))
+    }
+}
+
+impl<'n> ser::Serializer for ScalarSerializer<'n> {
+    type Ok = Scalar;
+    type Error = Error;
+    type SerializeSeq = Impossible<Scalar, Error>;
+    type SerializeTuple = Impossible<Scalar, Error>;
+    type SerializeTupleStruct = Impossible<Scalar, Error>;
+    type SerializeTupleVariant = Impossible<Scalar, Error>;
+    type SerializeMap = Impossible<Scalar, Error>;
+    type SerializeStruct = Impossible<Scalar, Error>;
+    type SerializeStructVariant = Impossible<Scalar, Error>;
+
+    fn serialize_bool(self, value: bool) -> Result<Scalar, Error> {
+        let value = if value { "TRUE" } else { "FALSE" };
+        Ok(Scalar::typed(value.to_string(), ValueType::Boolean))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Scalar, Error> {
+        self.integer(i64::from(value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Scalar, Error> {
+        self.integer(i64::from(value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Scalar, Error> {
+        self.integer(i64::from(value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Scalar, Error> {
+        self.integer(value)
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Scalar, Error> {
+        self.integer(i64::from(value))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Scalar, Error> {
+        self.integer(i64::from(value))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Scalar, Error> {
+        self.integer(i64::from(value))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Scalar, Error> {
+        if value > i64::max_value() as u64 {
+            return Err(invalid_value(self.name, ValueType::Integer));
+        }
+        self.integer(value as i64)
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Scalar, Error> {
+        self.float(value.is_finite(), value == 0.0, value.to_string())
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Scalar, Error> {
+        self.float(value.is_finite(), value == 0.0, value.to_string())
+    }
+
+    fn serialize_char(self, value: char) -> Result<Scalar, Error> {
+        Ok(Scalar::text(value.to_string()))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Scalar, Error> {
+        Ok(Scalar::text(value.to_string()))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Scalar, Error> {
+        Ok(Scalar::typed(encode_base64(value), ValueType::Binary))
+    }
+
+    fn serialize_none(self) -> Result<Scalar, Error> {
+        Err(unsupported("a value"))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Scalar, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Scalar, Error> {
+        Err(unsupported("a value"))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Scalar, Error> {
+        Err(unsupported("a value"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Scalar, Error> {
+        Ok(Scalar::text(to_name(variant)))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Scalar, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Scalar, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value_type = ValueType::from_name(&to_name(variant))
+            .ok_or_else(|| unsupported("a variant named after a value type"))?;
+        let scalar = value.serialize(self)?;
+        Ok(Scalar::typed(scalar.value, value_type))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(unsupported("a single value"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(unsupported("a single value"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(unsupported("a single value"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(unsupported("a single value"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(unsupported("a single value"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(unsupported("a single value"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(unsupported("a single value"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_component, to_name, to_string, Error};
+    use serde::{Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    struct Bytes(&'static [u8]);
+
+    impl Serialize for Bytes {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(to_name("dtstart"), "DTSTART");
+        assert_eq!(to_name("recurrence_id"), "RECURRENCE-ID");
+        assert_eq!(to_name("NeedsAction"), "NEEDS-ACTION");
+        assert_eq!(to_name("X-WR-CALNAME"), "X-WR-CALNAME");
+    }
+
+    #[derive(Serialize)]
+    enum Start {
+        Date(&'static str),
+    }
+
+    #[derive(Serialize)]
+    struct Member {
+        value: &'static str,
+        member: Vec<&'static str>,
+        cn: Option<&'static str>,
+    }
+
+    #[derive(Serialize)]
+    struct Reminder {
+        action: &'static str,
+        trigger: &'static str,
+    }
+
+    #[derive(Serialize)]
+    struct Task {
+        uid: &'static str,
+        dtstart: Start,
+        priority: u8,
+        x_done: bool,
+        x_rating: f64,
+        attach: Bytes,
+        categories: Vec<&'static str>,
+        description: Option<&'static str>,
+        attendee: Member,
+        valarm: Vec<Reminder>,
+    }
+
+    #[test]
+    fn component() {
+        let task = Task {
+            uid: "1",
+            dtstart: Start::Date("20190101"),
+            priority: 1,
+            x_done: false,
+            x_rating: -0.0,
+            attach: Bytes(b"ics"),
+            categories: vec!["WORK", "a;b"],
+            description: None,
+            attendee: Member {
+                value: "mailto:c@x.org",
+                member: vec!["mailto:a@x.org", "mailto:b@x.org"],
+                cn: None,
+            },
+            valarm: vec![Reminder {
+                action: "AUDIO",
+                trigger: "-PT15M",
+            }],
+        };
+        assert_eq!(
+            to_string("VTODO", &task).unwrap(),
+            "BEGIN:VTODO\r\n\
+             UID:1\r\n\
+             DTSTART;VALUE=DATE:20190101\r\n\
+             PRIORITY:1\r\n\
+             X-DONE;VALUE=BOOLEAN:FALSE\r\n\
+             X-RATING;VALUE=FLOAT:0\r\n\
+             ATTACH;ENCODING=BASE64;VALUE=BINARY:aWNz\r\n\
+             CATEGORIES:WORK\r\n\
+             CATEGORIES:a\\;b\r\n\
+             ATTENDEE;MEMBER=\"mailto:a@x.org\",\"mailto:b@x.org\":mailto:c@x.org\r\n\
+             BEGIN:VALARM\r\n\
+             ACTION:AUDIO\r\n\
+             TRIGGER:-PT15M\r\n\
+             END:VALARM\r\n\
+             END:VTODO\r\n"
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert("summary", "Meeting");
+        let component = to_component("VEVENT", &map).unwrap();
+        assert_eq!(
+            component.to_string(),
+            "BEGIN:VEVENT\r\nSUMMARY:Meeting\r\nEND:VEVENT\r\n"
+        );
+    }
+
+    #[test]
+    fn unsupported_component() {
+        assert_eq!(
+            to_component("VEVENT", "text"),
+            Err(Error::Unsupported {
+                expected: "a struct or map"
+            })
+        );
+    }
+
+    #[test]
+    fn nested_sequences() {
+        #[derive(Serialize)]
+        struct Nested {
+            categories: Vec<Vec<&'static str>>,
+        }
+        assert!(to_string(
+            "VEVENT",
+            &Nested {
+                categories: vec![vec!["A"]]
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn numbers_out_of_range() {
+        #[derive(Serialize)]
+        struct Large {
+            sequence: u64,
+            x_rating: f64,
+        }
+        let large = Large {
+            sequence: 1 << 40,
+            x_rating: 1.0,
+        };
+        assert!(to_string("VEVENT", &large).is_err());
+        let infinite = Large {
+            sequence: 1,
+            x_rating: std::f64::INFINITY,
+        };
+        assert!(to_string("VEVENT", &infinite).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::components::is_multi_valued;
 use std::borrow::Cow;
 
 /// Escapes comma, semicolon, backslash and newline character by prepending a
@@ -59,9 +60,248 @@ where
     }
 }
 
+// Reverses escape_text. Unknown escape sequences are kept.
+pub(crate) fn unescape_text(input: &str) -> Cow<'_, str> {
+    if !input.contains('\\') {
+        return Cow::Borrowed(input);
+    }
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => output.push('\n'),
+            Some(c @ '\\') | Some(c @ ',') | Some(c @ ';') => output.push(c),
+            Some(c) => {
+                output.push('\\');
+                output.push(c);
+            }
+            None => output.push('\\'),
+        }
+    }
+    Cow::Owned(output)
+}
+
+// Splits a list of text values at the unescaped commas and unescapes the
+// values.
+pub(crate) fn split_text_values(input: &str) -> Vec<Cow<'_, str>> {
+    let mut values = Vec::new();
+    let mut is_escaped = false;
+    let mut start = 0;
+    for (index, c) in input.char_indices() {
+        match c {
+            ',' if !is_escaped => {
+                values.push(unescape_text(&input[start..index]));
+                start = index + 1;
+            }
+            '\\' => {
+                is_escaped = !is_escaped;
+                continue;
+            }
+            _ => (),
+        }
+        is_escaped = false;
+    }
+    values.push(unescape_text(&input[start..]));
+    values
+}
+
+// Splits a list of values that are not text at the commas.
+pub(crate) fn split_values(input: &str) -> Vec<Cow<'_, str>> {
+    input.split(',').map(Cow::Borrowed).collect()
+}
+
+// Parameter values that contain a colon, semicolon or comma must be quoted,
+// as well as values with a DQUOTE if it is caret encoded. The values of
+// multi-valued parameters are stored as a list of quoted and already encoded
+// values which are written as they are.
+pub(crate) fn quote_param_value<'v>(key: &str, input: &'v str) -> Cow<'v, str> {
+    if is_multi_valued(key) && is_quoted(input) {
+        Cow::Borrowed(input)
+    } else if input.contains(|c| c == ':' || c == ';' || c == ',' || (CARET_ENCODING && c == '"')) {
+        Cow::Owned(format!("\"{}\"", encode_param_value(input)))
+    } else {
+        encode_param_value(input)
+    }
+}
+
+// Quotes and encodes a single value of a multi-valued parameter. Unlike other
+// parameter values, those are always quoted and they are encoded right away
+// because a DQUOTE cannot be part of a quoted value.
+pub(crate) fn quote_param_list_item(input: &str) -> String {
+    format!("\"{}\"", encode_param_value(input))
+}
+
+// DQUOTE and control characters cannot be represented in parameter values and
+// are removed unless the RFC6868 caret encoding is enabled which encodes
+// DQUOTE, newlines and the caret itself.
+fn encode_param_value(input: &str) -> Cow<'_, str> {
+    if !input.contains(needs_encoding) {
+        return Cow::Borrowed(input);
+    }
+    let mut output = String::with_capacity(input.len() + 2);
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if CARET_ENCODING => output.push_str("^'"),
+            '^' if CARET_ENCODING => output.push_str("^^"),
+            // \r\n and \r are normalized to a single newline
+            '\r' if CARET_ENCODING && chars.peek() != Some(&'\n') => output.push_str("^n"),
+            '\n' if CARET_ENCODING => output.push_str("^n"),
+            c if needs_encoding(c) => (),
+            c => output.push(c),
+        }
+    }
+    Cow::Owned(output)
+}
+
+fn needs_encoding(c: char) -> bool {
+    c == '"' || c.is_control() && c != '\t' || CARET_ENCODING && c == '^'
+}
+
+// Splits the value of a multi-valued parameter at the commas between the
+// (quoted) values, unquotes and decodes them.
+pub(crate) fn split_param_values(input: &str) -> Vec<Cow<'_, str>> {
+    let mut values = Vec::new();
+    let mut is_quoted = false;
+    let mut start = 0;
+    for (index, c) in input.char_indices() {
+        match c {
+            '"' => is_quoted = !is_quoted,
+            ',' if !is_quoted => {
+                values.push(decode_param_value(unquote_param_value(
+                    &input[start..index],
+                )));
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    values.push(decode_param_value(unquote_param_value(&input[start..])));
+    values
+}
+
+// Removes the quotes around a parameter value.
+pub(crate) fn unquote_param_value(input: &str) -> &str {
+    if input.len() >= 2 && input.starts_with('"') && input.ends_with('"') {
+        &input[1..input.len() - 1]
+    } else {
+        input
+    }
+}
+
+// Decodes the RFC6868 caret encoding of a parsed parameter value if it is
+// enabled.
+pub(crate) fn decode_param_value(input: &str) -> Cow<'_, str> {
+    if !CARET_ENCODING || !input.contains('^') {
+        return Cow::Borrowed(input);
+    }
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c != '^' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('^') => output.push('^'),
+            Some('n') | Some('N') => output.push('\n'),
+            Some('\'') => output.push('"'),
+            // Unknown sequences are left as they are.
+            Some(c) => {
+                output.push('^');
+                output.push(c);
+            }
+            None => output.push('^'),
+        }
+    }
+    Cow::Owned(output)
+}
+
+const CARET_ENCODING: bool = cfg!(feature = "rfc6868");
+
+// Checks whether the input is a comma separated list of quoted strings.
+pub(crate) fn is_quoted(input: &str) -> bool {
+    let mut rest = input;
+    loop {
+        if !rest.starts_with('"') {
+            return false;
+        }
+        let end = match rest[1..].find('"') {
+            Some(end) => end + 2,
+            None => return false,
+        };
+        rest = &rest[end..];
+        if rest.is_empty() {
+            return true;
+        } else if rest.starts_with(',') {
+            rest = &rest[1..];
+        } else {
+            return false;
+        }
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Encodes bytes with the BASE64 encoding as specified in RFC4648 with padding.
+pub(crate) fn encode_base64(input: &[u8]) -> String {
+    let mut output = String::with_capacity((input.len() + 2) / 3 * 4);
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).cloned().unwrap_or(0),
+            chunk.get(2).cloned().unwrap_or(0),
+        ];
+        let indices = [
+            b[0] >> 2,
+            (b[0] & 0x03) << 4 | b[1] >> 4,
+            (b[1] & 0x0f) << 2 | b[2] >> 6,
+            b[2] & 0x3f,
+        ];
+        for (i, &index) in indices.iter().enumerate() {
+            if i <= chunk.len() {
+                output.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+// Decodes text with the BASE64 encoding as specified in RFC4648. Padding is
+// optional but other characters are not allowed.
+pub(crate) fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let input = input.trim_end_matches('=').as_bytes();
+    if input.len() % 4 == 1 {
+        return None;
+    }
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    for chunk in input.chunks(4) {
+        let mut bits: u32 = 0;
+        for &b in chunk {
+            let index = BASE64_ALPHABET.iter().position(|&c| c == b)?;
+            bits = bits << 6 | index as u32;
+        }
+        bits <<= 6 * (4 - chunk.len()) as u32;
+        let bytes = [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8];
+        output.extend_from_slice(&bytes[..chunk.len() - 1]);
+    }
+    Some(output)
+}
+
 #[cfg(test)]
 mod escape_text_tests {
-    use super::escape_text;
+    use super::{
+        decode_base64, decode_param_value, encode_base64, escape_text, quote_param_list_item,
+        quote_param_value, split_param_values, split_text_values, unescape_text,
+        unquote_param_value,
+    };
 
     #[test]
     fn escaped_chars() {
@@ -73,7 +313,7 @@ mod escape_text_tests {
     #[test]
     fn no_escaped_chars() {
         let s = "This is a simple sentence.";
-        let expected = s.clone();
+        let expected = s;
         assert_eq!(expected, escape_text(s));
     }
 
@@ -89,4 +329,123 @@ mod escape_text_tests {
         );
         assert_eq!(expected_value, property.value);
     }
+
+    #[test]
+    fn quoted_param_values() {
+        assert_eq!(
+            quote_param_value("TZID", "America/New_York"),
+            "America/New_York"
+        );
+        assert_eq!(
+            quote_param_value("SENT-BY", "mailto:jsmith@example.com"),
+            "\"mailto:jsmith@example.com\""
+        );
+        assert_eq!(
+            quote_param_value(
+                "MEMBER",
+                "\"mailto:a@example.com\",\"mailto:b@example.com\""
+            ),
+            "\"mailto:a@example.com\",\"mailto:b@example.com\""
+        );
+    }
+
+    #[test]
+    fn unescaped_chars() {
+        let s = "\\,\\n\\;:\\\\ \\N\\x";
+        let expected = ",\n;:\\ \n\\x";
+        assert_eq!(expected, unescape_text(s));
+    }
+
+    #[test]
+    fn split_text_list() {
+        assert_eq!(
+            split_text_values("Doe\\, John,C:\\\\,,Line\\nbreak"),
+            vec!["Doe, John", "C:\\", "", "Line\nbreak"]
+        );
+    }
+
+    #[test]
+    fn split_param_list() {
+        assert_eq!(
+            split_param_values("\"mailto:a@example.com\",\"mailto:b,c@example.com\""),
+            vec!["mailto:a@example.com", "mailto:b,c@example.com"]
+        );
+        assert_eq!(split_param_values("GROUP"), vec!["GROUP"]);
+        assert_eq!(unquote_param_value("\"Smiley ^^ face\""), "Smiley ^^ face");
+    }
+
+    #[cfg(not(feature = "rfc6868"))]
+    #[test]
+    fn removed_param_chars() {
+        assert_eq!(
+            quote_param_value("CN", "John \"Johnny\" Doe"),
+            "John Johnny Doe"
+        );
+        assert_eq!(quote_param_value("CN", "\"Johnny\""), "Johnny");
+        assert_eq!(quote_param_value("CN", "Doe, \"John\"\n"), "\"Doe, John\"");
+        assert_eq!(decode_param_value("^'Doe^'"), "^'Doe^'");
+        assert_eq!(
+            split_param_values("\"Smiley ^^ face\",\"a\""),
+            vec!["Smiley ^^ face", "a"]
+        );
+        assert_eq!(
+            quote_param_list_item("mailto:\"a\"@example.com"),
+            "\"mailto:a@example.com\""
+        );
+    }
+
+    #[cfg(feature = "rfc6868")]
+    #[test]
+    fn caret_encoding() {
+        assert_eq!(
+            quote_param_value("CN", "John \"Johnny\" Doe"),
+            "\"John ^'Johnny^' Doe\""
+        );
+        assert_eq!(quote_param_value("CN", "\"Johnny\""), "\"^'Johnny^'\"");
+        assert_eq!(
+            quote_param_value("CN", "Doe, \"John\"\r\n^_^"),
+            "\"Doe, ^'John^'^n^^_^^\""
+        );
+        assert_eq!(
+            decode_param_value("Doe, ^'John^'^n^^_^^"),
+            "Doe, \"John\"\n^_^"
+        );
+        assert_eq!(decode_param_value("^x^"), "^x^");
+        assert_eq!(
+            split_param_values("\"Smiley ^^ face\",\"^'a^'\""),
+            vec!["Smiley ^ face", "\"a\""]
+        );
+        assert_eq!(
+            quote_param_list_item("mailto:\"a\"@example.com"),
+            "\"mailto:^'a^'@example.com\""
+        );
+        let list = format!(
+            "{},{}",
+            quote_param_list_item("mailto:a^b@example.com"),
+            quote_param_list_item("mailto:c@example.com")
+        );
+        assert_eq!(
+            quote_param_value("MEMBER", &list),
+            "\"mailto:a^^b@example.com\",\"mailto:c@example.com\""
+        );
+    }
+
+    #[test]
+    fn base64() {
+        let cases: [(&[u8], &str); 7] = [
+            (b"", ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"fooba", "Zm9vYmE="),
+            (b"\xff\xfe\x00", "//4A"),
+        ];
+        for &(bytes, text) in &cases {
+            assert_eq!(encode_base64(bytes), text);
+            assert_eq!(decode_base64(text).as_ref().map(Vec::as_slice), Some(bytes));
+        }
+        assert_eq!(decode_base64("Zm9vY"), None);
+        assert_eq!(decode_base64("Zm9v\r\n"), None);
+    }
 }
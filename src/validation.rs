@@ -0,0 +1,851 @@
+//! Checks for values and parameters of properties.
+//!
+//! Every property specified in RFC5545 and RFC7986 has a default value type
+//! and sometimes alternative value types which can be selected with the
+//! `VALUE` parameter. The builder types only accept text which is why nothing
+//! stops a value like `PRIORITY:high` from being written. Likewise, most
+//! parameters are only meaningful on certain properties, e.g. `PARTSTAT` on
+//! `ATTENDEE`. The functions of this module can be used to check the
+//! properties before they are written.
+//!
+//! # Example
+//! ```
+//! use ics::components::Property;
+//! use ics::properties::{DtStart, Priority};
+//! use ics::parameters::Value;
+//! use ics::validation::{check_value, ValueType};
+//!
+//! assert!(check_value(&Priority::new("1").into()).is_ok());
+//! assert!(check_value(&Priority::new("high").into()).is_err());
+//!
+//! // An explicit `VALUE` parameter is honoured.
+//! let mut date = DtStart::new("20180906");
+//! assert!(check_value(&date.clone().into()).is_err());
+//! date.add(Value::DATE);
+//! assert!(check_value(&date.into()).is_ok());
+//! ```
+//!
+//! # Parameters
+//! ```
+//! use ics::parameters::{PartStat, Related};
+//! use ics::properties::{Attendee, Trigger};
+//! use ics::validation::check_parameters;
+//!
+//! let mut attendee = Attendee::new("mailto:jsmith@example.com");
+//! attendee.add(PartStat::ACCEPTED);
+//! assert!(check_parameters(&attendee.into()).is_ok());
+//!
+//! let mut trigger = Trigger::new("-PT15M");
+//! trigger.add(PartStat::ACCEPTED);
+//! trigger.add(Related::End);
+//! assert!(check_parameters(&trigger.into()).is_err());
+//! ```
+//! For more information on value types, please refer to the specification [RFC5545 3.3. Property Value Data Types](https://tools.ietf.org/html/rfc5545#section-3.3).
+use crate::components::{find_parameter, Component, Property};
+use std::error;
+use std::fmt;
+
+/// The value types specified in RFC5545.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ValueType {
+    /// `BINARY`
+    Binary,
+    /// `BOOLEAN`
+    Boolean,
+    /// `CAL-ADDRESS`
+    CalAddress,
+    /// `DATE`
+    Date,
+    /// `DATE-TIME`
+    DateTime,
+    /// `DURATION`
+    Duration,
+    /// `FLOAT`
+    Float,
+    /// `INTEGER`
+    Integer,
+    /// `PERIOD`
+    Period,
+    /// `RECUR`
+    Recur,
+    /// `TEXT`
+    Text,
+    /// `TIME`
+    Time,
+    /// `URI`
+    Uri,
+    /// `UTC-OFFSET`
+    UtcOffset,
+}
+
+impl ValueType {
+    /// Returns the name of the value type as used in the `VALUE` parameter.
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueType::Binary => "BINARY",
+            ValueType::Boolean => "BOOLEAN",
+            ValueType::CalAddress => "CAL-ADDRESS",
+            ValueType::Date => "DATE",
+            ValueType::DateTime => "DATE-TIME",
+            ValueType::Duration => "DURATION",
+            ValueType::Float => "FLOAT",
+            ValueType::Integer => "INTEGER",
+            ValueType::Period => "PERIOD",
+            ValueType::Recur => "RECUR",
+            ValueType::Text => "TEXT",
+            ValueType::Time => "TIME",
+            ValueType::Uri => "URI",
+            ValueType::UtcOffset => "UTC-OFFSET",
+        }
+    }
+
+    /// Looks up a value type by its name. The name is compared
+    /// case-insensitively. IANA and non-standard value types return `None`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        VALUE_TYPES
+            .iter()
+            .find(|value_type| value_type.name().eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// Checks whether the given text is a valid value of this type.
+    pub fn is_valid(self, value: &str) -> bool {
+        match self {
+            ValueType::Binary => is_binary(value),
+            ValueType::Boolean => is_boolean(value),
+            ValueType::CalAddress | ValueType::Uri => is_uri(value),
+            ValueType::Date => is_date(value),
+            ValueType::DateTime => is_date_time(value),
+            ValueType::Duration => is_duration(value),
+            ValueType::Float => is_float(value),
+            ValueType::Integer => is_integer(value),
+            ValueType::Period => is_period(value),
+            ValueType::Recur => is_recur(value),
+            ValueType::Text => is_text(value),
+            ValueType::Time => is_time(value),
+            ValueType::UtcOffset => is_utc_offset(value),
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+const VALUE_TYPES: [ValueType; 14] = [
+    ValueType::Binary,
+    ValueType::Boolean,
+    ValueType::CalAddress,
+    ValueType::Date,
+    ValueType::DateTime,
+    ValueType::Duration,
+    ValueType::Float,
+    ValueType::Integer,
+    ValueType::Period,
+    ValueType::Recur,
+    ValueType::Text,
+    ValueType::Time,
+    ValueType::Uri,
+    ValueType::UtcOffset,
+];
+
+use self::ValueType::*;
+
+// The first value type is the default value type of the property.
+const PROPERTIES: &[(&str, &[ValueType])] = &[
+    ("CALSCALE", &[Text]),
+    ("METHOD", &[Text]),
+    ("PRODID", &[Text]),
+    ("VERSION", &[Text]),
+    ("ATTACH", &[Uri, Binary]),
+    ("CATEGORIES", &[Text]),
+    ("CLASS", &[Text]),
+    ("COMMENT", &[Text]),
+    ("DESCRIPTION", &[Text]),
+    ("GEO", &[Float]),
+    ("LOCATION", &[Text]),
+    ("PERCENT-COMPLETE", &[Integer]),
+    ("PRIORITY", &[Integer]),
+    ("RESOURCES", &[Text]),
+    ("STATUS", &[Text]),
+    ("SUMMARY", &[Text]),
+    ("COMPLETED", &[DateTime]),
+    ("DTEND", &[DateTime, Date]),
+    ("DUE", &[DateTime, Date]),
+    ("DTSTART", &[DateTime, Date]),
+    ("DURATION", &[Duration]),
+    ("FREEBUSY", &[Period]),
+    ("TRANSP", &[Text]),
+    ("TZID", &[Text]),
+    ("TZNAME", &[Text]),
+    ("TZOFFSETFROM", &[UtcOffset]),
+    ("TZOFFSETTO", &[UtcOffset]),
+    ("TZURL", &[Uri]),
+    ("ATTENDEE", &[CalAddress]),
+    ("CONTACT", &[Text]),
+    ("ORGANIZER", &[CalAddress]),
+    ("RECURRENCE-ID", &[DateTime, Date]),
+    ("RELATED-TO", &[Text]),
+    ("URL", &[Uri]),
+    ("UID", &[Text]),
+    ("EXDATE", &[DateTime, Date]),
+    ("RDATE", &[DateTime, Date, Period]),
+    ("RRULE", &[Recur]),
+    ("ACTION", &[Text]),
+    ("REPEAT", &[Integer]),
+    ("TRIGGER", &[Duration, DateTime]),
+    ("CREATED", &[DateTime]),
+    ("DTSTAMP", &[DateTime]),
+    ("LAST-MODIFIED", &[DateTime]),
+    ("SEQUENCE", &[Integer]),
+    ("REQUEST-STATUS", &[Text]),
+];
+
+#[cfg(feature = "rfc7986")]
+const RFC7986_PROPERTIES: &[(&str, &[ValueType])] = &[
+    ("NAME", &[Text]),
+    ("REFRESH-INTERVAL", &[Duration]),
+    ("SOURCE", &[Uri]),
+    ("COLOR", &[Text]),
+    ("CONFERENCE", &[Uri]),
+    ("IMAGE", &[Uri, Binary]),
+];
+
+#[cfg(not(feature = "rfc7986"))]
+const RFC7986_PROPERTIES: &[(&str, &[ValueType])] = &[];
+
+// Properties whose value is a comma separated list of values.
+const LIST_PROPERTIES: [&str; 5] = ["CATEGORIES", "RESOURCES", "FREEBUSY", "EXDATE", "RDATE"];
+
+/// Returns the value types a property allows. The first value type is the
+/// default value type that is used if no `VALUE` parameter is present.
+/// IANA and non-standard properties return `None`.
+///
+/// # Example
+/// ```
+/// use ics::validation::{value_types, ValueType};
+///
+/// assert_eq!(value_types("PRIORITY"), Some(&[ValueType::Integer][..]));
+/// assert_eq!(value_types("X-CUSTOM"), None);
+/// ```
+pub fn value_types(name: &str) -> Option<&'static [ValueType]> {
+    PROPERTIES
+        .iter()
+        .chain(RFC7986_PROPERTIES)
+        .find(|(property, _)| property.eq_ignore_ascii_case(name))
+        .map(|&(_, value_types)| value_types)
+}
+
+/// Returns the value type of a property. An explicit `VALUE` parameter takes
+/// precedence over the default value type. If neither is known, `None` is
+/// returned.
+pub fn value_type(property: &Property) -> Option<ValueType> {
+    match find_parameter(&property.parameters, "VALUE") {
+        Some(value) => ValueType::from_name(value),
+        None => value_types(&property.key).map(|value_types| value_types[0]),
+    }
+}
+
+/// Checks whether the value of a property can be parsed as its value type.
+///
+/// An explicit `VALUE` parameter is honoured but must be one of the value
+/// types allowed for the property. Values of IANA and non-standard properties
+/// and value types are not checked.
+pub fn check_value(property: &Property) -> Result<(), Error> {
+    if let Some(value) = find_parameter(&property.parameters, "VALUE") {
+        if let Some(value_types) = value_types(&property.key) {
+            let allowed = value_types
+                .iter()
+                .any(|value_type| value_type.name().eq_ignore_ascii_case(value));
+            if !allowed {
+                return Err(Error::UnsupportedValueType {
+                    property: property.key.to_string(),
+                    value_type: value.to_string(),
+                });
+            }
+        }
+    }
+
+    let value_type = match value_type(property) {
+        Some(value_type) => value_type,
+        None => return Ok(()),
+    };
+    let valid = if property.key.eq_ignore_ascii_case("GEO") {
+        is_geo(&property.value)
+    } else if is_list(&property.key) && value_type != ValueType::Text {
+        property
+            .value
+            .split(',')
+            .all(|value| value_type.is_valid(value))
+    } else {
+        value_type.is_valid(&property.value)
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidValue {
+            property: property.key.to_string(),
+            value_type,
+        })
+    }
+}
+
+/// Checks whether the parameters of a property are allowed on it.
+///
+/// Parameters specified in RFC5545 and RFC7986 are only meaningful on certain
+/// properties, e.g. `RSVP` only on `ATTENDEE` or `RANGE` only on
+/// `RECURRENCE-ID`. The `VALUE` parameter as well as IANA and non-standard
+/// parameters and properties are not checked.
+pub fn check_parameters(property: &Property) -> Result<(), Error> {
+    if value_types(&property.key).is_none() {
+        return Ok(());
+    }
+    for parameter in property.parameters.keys() {
+        let allowed = match parameter_properties(parameter) {
+            Some(properties) => properties
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&property.key)),
+            None => true,
+        };
+        if !allowed {
+            return Err(Error::UnsupportedParameter {
+                property: property.key.to_string(),
+                parameter: parameter.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks the values and parameters of all properties of a component and its
+/// sub-components. The first invalid property is returned as error.
+pub fn check_component(component: &Component) -> Result<(), Error> {
+    for property in &component.properties {
+        check_value(property)?;
+        check_parameters(property)?;
+    }
+    for component in &component.subcomponents {
+        check_component(component)?;
+    }
+    Ok(())
+}
+
+const TEXT_PROPERTIES: &[&str] = &[
+    "COMMENT",
+    "DESCRIPTION",
+    "LOCATION",
+    "RESOURCES",
+    "SUMMARY",
+    "CONTACT",
+    #[cfg(feature = "rfc7986")]
+    "NAME",
+    #[cfg(feature = "rfc7986")]
+    "IMAGE",
+];
+
+const LANGUAGE_PROPERTIES: &[&str] = &[
+    "CATEGORIES",
+    "COMMENT",
+    "DESCRIPTION",
+    "LOCATION",
+    "RESOURCES",
+    "SUMMARY",
+    "TZNAME",
+    "ATTENDEE",
+    "CONTACT",
+    "ORGANIZER",
+    "REQUEST-STATUS",
+    #[cfg(feature = "rfc7986")]
+    "NAME",
+    #[cfg(feature = "rfc7986")]
+    "CONFERENCE",
+];
+
+const BINARY_PROPERTIES: &[&str] = &[
+    "ATTACH",
+    #[cfg(feature = "rfc7986")]
+    "IMAGE",
+];
+
+const DATE_PROPERTIES: &[&str] = &[
+    "DTSTART",
+    "DTEND",
+    "DUE",
+    "EXDATE",
+    "RDATE",
+    "RECURRENCE-ID",
+];
+
+// Returns the properties a parameter can be specified on. Parameters that can
+// be specified on any property or are unknown return `None`.
+fn parameter_properties(name: &str) -> Option<&'static [&'static str]> {
+    let properties: &[&str] = match name.to_ascii_uppercase().as_str() {
+        "ALTREP" => TEXT_PROPERTIES,
+        "CN" | "DIR" | "SENT-BY" => &["ATTENDEE", "ORGANIZER"],
+        "CUTYPE" | "DELEGATED-FROM" | "DELEGATED-TO" | "MEMBER" | "PARTSTAT" | "ROLE" | "RSVP" => {
+            &["ATTENDEE"]
+        }
+        "ENCODING" | "FMTTYPE" => BINARY_PROPERTIES,
+        "FBTYPE" => &["FREEBUSY"],
+        "LANGUAGE" => LANGUAGE_PROPERTIES,
+        "RANGE" => &["RECURRENCE-ID"],
+        "RELATED" => &["TRIGGER"],
+        "RELTYPE" => &["RELATED-TO"],
+        "TZID" => DATE_PROPERTIES,
+        #[cfg(feature = "rfc7986")]
+        "DISPLAY" => &["IMAGE"],
+        #[cfg(feature = "rfc7986")]
+        "EMAIL" => &["ATTENDEE", "ORGANIZER"],
+        #[cfg(feature = "rfc7986")]
+        "FEATURE" | "LABEL" => &["CONFERENCE"],
+        _ => return None,
+    };
+    Some(properties)
+}
+
+pub(crate) fn is_list(name: &str) -> bool {
+    LIST_PROPERTIES
+        .iter()
+        .any(|property| property.eq_ignore_ascii_case(name))
+}
+
+/// The error type for failed checks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// The value of a property cannot be parsed as its value type.
+    InvalidValue {
+        /// Name of the property
+        property: String,
+        /// Expected value type
+        value_type: ValueType,
+    },
+    /// The `VALUE` parameter specifies a value type that the property does not
+    /// allow.
+    UnsupportedValueType {
+        /// Name of the property
+        property: String,
+        /// Value of the `VALUE` parameter
+        value_type: String,
+    },
+    /// The parameter is not allowed on the property.
+    UnsupportedParameter {
+        /// Name of the property
+        property: String,
+        /// Name of the parameter
+        parameter: String,
+    },
+    /// A typed value cannot be created from or parsed as the given format.
+    InvalidFormat {
+        /// Name of the format, e.g. `CAL-ADDRESS`
+        format: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidValue {
+                property,
+                value_type,
+            } => write!(f, "value of {} is not a valid {}", property, value_type),
+            Error::UnsupportedValueType {
+                property,
+                value_type,
+            } => write!(
+                f,
+                "{} does not allow the value type {}",
+                property, value_type
+            ),
+            Error::UnsupportedParameter {
+                property,
+                parameter,
+            } => write!(f, "{} does not allow the parameter {}", property, parameter),
+            Error::InvalidFormat { format } => write!(f, "invalid {}", format),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+fn is_digits(input: &str, len: usize) -> bool {
+    input.len() == len && input.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_binary(input: &str) -> bool {
+    let input = input.as_bytes();
+    if input.len() % 4 != 0 {
+        return false;
+    }
+    let padding = input.iter().rev().take_while(|&&b| b == b'=').count();
+    padding <= 2
+        && input[..input.len() - padding]
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+}
+
+fn is_boolean(input: &str) -> bool {
+    input.eq_ignore_ascii_case("TRUE") || input.eq_ignore_ascii_case("FALSE")
+}
+
+pub(crate) fn is_uri(input: &str) -> bool {
+    let colon = match input.find(':') {
+        Some(index) => index,
+        None => return false,
+    };
+    let mut scheme = input[..colon].bytes();
+    let starts_with_alpha = scheme.next().map_or(false, |b| b.is_ascii_alphabetic());
+    starts_with_alpha
+        && scheme.all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-' || b == b'.')
+        && input.len() > colon + 1
+        && !input
+            .bytes()
+            .any(|b| b.is_ascii_whitespace() || b.is_ascii_control())
+}
+
+pub(crate) fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+fn is_date(input: &str) -> bool {
+    if !is_digits(input, 8) {
+        return false;
+    }
+    let year = input[..4].parse().unwrap_or(0);
+    let month = input[4..6].parse().unwrap_or(0);
+    let day = input[6..].parse().unwrap_or(0);
+    day >= 1 && day <= days_in_month(year, month)
+}
+
+fn is_time(input: &str) -> bool {
+    let input = if input.ends_with('Z') {
+        &input[..input.len() - 1]
+    } else {
+        input
+    };
+    if !is_digits(input, 6) {
+        return false;
+    }
+    let hour: u32 = input[..2].parse().unwrap_or(99);
+    let minute: u32 = input[2..4].parse().unwrap_or(99);
+    // 60 is allowed for leap seconds
+    let second: u32 = input[4..].parse().unwrap_or(99);
+    hour < 24 && minute < 60 && second <= 60
+}
+
+fn is_date_time(input: &str) -> bool {
+    match input.find('T') {
+        Some(8) => is_date(&input[..8]) && is_time(&input[9..]),
+        _ => false,
+    }
+}
+
+// Removes at most one leading sign.
+fn strip_sign(input: &str) -> &str {
+    if input.starts_with('+') || input.starts_with('-') {
+        &input[1..]
+    } else {
+        input
+    }
+}
+
+fn is_duration(input: &str) -> bool {
+    let input = strip_sign(input);
+    if !input.starts_with('P') {
+        return false;
+    }
+    let input = &input[1..];
+    // dur-week
+    if input.ends_with('W') {
+        return input.len() > 1 && input[..input.len() - 1].bytes().all(|b| b.is_ascii_digit());
+    }
+    let (date, time) = match input.find('T') {
+        Some(index) => (&input[..index], Some(&input[index + 1..])),
+        None => (input, None),
+    };
+    let date_valid = date.is_empty() || is_duration_units(date, b"D");
+    let time_valid = time.map_or(!date.is_empty(), |time| is_duration_units(time, b"HMS"));
+    date_valid && time_valid
+}
+
+// Checks a sequence of digits with designators. The designators must appear
+// in order without gaps, e.g. `1H2M` or `2M3S` but not `1H3S`.
+fn is_duration_units(mut input: &str, units: &[u8]) -> bool {
+    let mut next = 0;
+    let mut first = true;
+    while !input.is_empty() {
+        let digits = input.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 || digits == input.len() {
+            return false;
+        }
+        let unit = input.as_bytes()[digits];
+        match units[next..].iter().position(|&u| u == unit) {
+            Some(offset) if first || offset == 0 => next += offset + 1,
+            _ => return false,
+        }
+        first = false;
+        input = &input[digits + 1..];
+    }
+    !first
+}
+
+pub(crate) fn is_float(input: &str) -> bool {
+    let input = strip_sign(input);
+    let mut parts = input.splitn(2, '.');
+    let integer = parts.next().unwrap_or("");
+    let fraction = parts.next();
+    !integer.is_empty()
+        && integer.bytes().all(|b| b.is_ascii_digit())
+        && fraction.map_or(true, |fraction| {
+            !fraction.is_empty() && fraction.bytes().all(|b| b.is_ascii_digit())
+        })
+}
+
+fn is_geo(input: &str) -> bool {
+    let mut parts = input.split(';');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(latitude), Some(longitude), None) => is_float(latitude) && is_float(longitude),
+        _ => false,
+    }
+}
+
+fn is_integer(input: &str) -> bool {
+    let digits = strip_sign(input);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) && input.parse::<i32>().is_ok()
+}
+
+fn is_period(input: &str) -> bool {
+    let mut parts = input.splitn(2, '/');
+    match (parts.next(), parts.next()) {
+        (Some(start), Some(end)) => {
+            is_date_time(start) && (is_date_time(end) || is_duration(end) && !end.starts_with('-'))
+        }
+        _ => false,
+    }
+}
+
+fn is_recur(input: &str) -> bool {
+    let mut has_frequency = false;
+    for part in input.split(';') {
+        let mut pair = part.splitn(2, '=');
+        let (key, value) = match (pair.next(), pair.next()) {
+            (Some(key), Some(value)) if !value.is_empty() => (key, value),
+            _ => return false,
+        };
+        let valid = match key {
+            "FREQ" => {
+                has_frequency = true;
+                [
+                    "SECONDLY", "MINUTELY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY",
+                ]
+                .contains(&value)
+            }
+            "UNTIL" => is_date(value) || is_date_time(value),
+            "COUNT" | "INTERVAL" => value.bytes().all(|b| b.is_ascii_digit()),
+            "WKST" => is_weekday(value),
+            "BYDAY" => value.split(',').all(|day| {
+                let weekday =
+                    day.trim_start_matches(|c: char| c == '+' || c == '-' || c.is_ascii_digit());
+                is_weekday(weekday)
+            }),
+            "BYSECOND" | "BYMINUTE" | "BYHOUR" | "BYMONTHDAY" | "BYYEARDAY" | "BYWEEKNO"
+            | "BYMONTH" | "BYSETPOS" => value.split(',').all(is_integer),
+            _ => key.starts_with("X-"),
+        };
+        if !valid {
+            return false;
+        }
+    }
+    has_frequency
+}
+
+fn is_weekday(input: &str) -> bool {
+    ["SU", "MO", "TU", "WE", "TH", "FR", "SA"].contains(&input)
+}
+
+// Line breaks and other control characters must be escaped in text values.
+fn is_text(input: &str) -> bool {
+    !input.chars().any(|c| c.is_control() && c != '\t')
+}
+
+pub(crate) fn is_utc_offset(input: &str) -> bool {
+    if !input.starts_with('+') && !input.starts_with('-') {
+        return false;
+    }
+    let (sign, digits) = input.split_at(1);
+    if !is_digits(digits, 4) && !is_digits(digits, 6) {
+        return false;
+    }
+    let hour: u32 = digits[..2].parse().unwrap_or(99);
+    let minute: u32 = digits[2..4].parse().unwrap_or(99);
+    let second: u32 = if digits.len() == 6 {
+        digits[4..].parse().unwrap_or(99)
+    } else {
+        0
+    };
+    let is_negative_zero = sign == "-" && hour == 0 && minute == 0 && second == 0;
+    hour < 24 && minute < 60 && second < 60 && !is_negative_zero
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Parameter, Property};
+
+    #[test]
+    fn date_and_time() {
+        assert!(is_date("20240229"));
+        assert!(!is_date("20230229"));
+        assert!(!is_date("2023-02-01"));
+        assert!(is_date_time("19980118T230000"));
+        assert!(is_date_time("19980119T070000Z"));
+        assert!(!is_date_time("19980119T250000Z"));
+        assert!(!is_date_time("19980119"));
+        assert!(is_time("235960"));
+        assert!(!is_time("2359"));
+    }
+
+    #[test]
+    fn duration() {
+        for valid in &[
+            "P15DT5H0M20S",
+            "P7W",
+            "-PT15M",
+            "+P1D",
+            "PT1H",
+            "PT1H30M",
+            "PT5S",
+        ] {
+            assert!(is_duration(valid), "{}", valid);
+        }
+        for invalid in &[
+            "P", "PT", "15M", "P1H", "PT1H5S", "PT1M1H", "P1W2D", "PT1.5H",
+        ] {
+            assert!(!is_duration(invalid), "{}", invalid);
+        }
+        assert!(!is_duration("+-P1D"));
+    }
+
+    #[test]
+    fn numbers() {
+        assert!(is_integer("-12"));
+        assert!(!is_integer("high"));
+        assert!(!is_integer("+-1"));
+        assert!(!is_integer("99999999999"));
+        assert!(is_float("37.386013"));
+        assert!(is_float("-122"));
+        assert!(!is_float("1."));
+        assert!(!is_float(".5"));
+        assert!(!is_float("+-1.5"));
+        assert!(!is_float("--1"));
+        assert!(is_geo("37.386013;-122.082932"));
+        assert!(!is_geo("37.386013,-122.082932"));
+    }
+
+    #[test]
+    fn others() {
+        assert!(is_utc_offset("-0500"));
+        assert!(is_utc_offset("+013045"));
+        assert!(!is_utc_offset("+1"));
+        assert!(!is_utc_offset("-0000"));
+        assert!(is_uri("mailto:jsmith@example.com"));
+        assert!(!is_uri("jsmith@example.com"));
+        assert!(is_period("19970101T180000Z/PT5H30M"));
+        assert!(is_period("19970101T180000Z/19970102T070000Z"));
+        assert!(is_recur(
+            "FREQ=MONTHLY;BYDAY=MO,-1FR;UNTIL=19971224T000000Z"
+        ));
+        assert!(!is_recur("BYDAY=MO"));
+        assert!(!is_recur("FREQ=FORTNIGHTLY"));
+        assert!(is_binary("SGVsbG8="));
+        assert!(!is_binary("SGVsbG8"));
+        assert!(!is_text("line\nbreak"));
+    }
+
+    #[test]
+    fn properties() {
+        assert!(check_value(&Property::new(
+            "EXDATE",
+            "19960402T010000Z,19960403T010000Z"
+        ))
+        .is_ok());
+        assert!(check_value(&Property::new("TZOFFSETFROM", "+1")).is_err());
+        assert!(check_value(&Property::new("X-CUSTOM", "anything")).is_ok());
+
+        let mut property = Property::new("ATTACH", "SGVsbG8=");
+        property.add(crate::parameters::Value::BINARY);
+        assert_eq!(check_value(&property), Ok(()));
+
+        let mut property = Property::new("PRIORITY", "1");
+        property.add(crate::parameters::Value::TEXT);
+        assert_eq!(
+            check_value(&property),
+            Err(Error::UnsupportedValueType {
+                property: "PRIORITY".to_string(),
+                value_type: "TEXT".to_string()
+            })
+        );
+
+        let mut property = Property::new("DTSTART", "20200101");
+        property.add(Parameter::new("value", "date"));
+        assert_eq!(value_type(&property), Some(ValueType::Date));
+        assert_eq!(check_value(&property), Ok(()));
+    }
+
+    #[test]
+    fn parameters() {
+        use crate::parameters::{CUType, Range, TzIDParam, RSVP};
+
+        let mut property = Property::new("ATTENDEE", "mailto:jsmith@example.com");
+        property.add(CUType::GROUP);
+        property.add(RSVP::True);
+        property.add(Parameter::new("X-CUSTOM", "VALUE"));
+        assert_eq!(check_parameters(&property), Ok(()));
+
+        let mut property = Property::new("ORGANIZER", "mailto:jsmith@example.com");
+        property.add(RSVP::True);
+        assert_eq!(
+            check_parameters(&property),
+            Err(Error::UnsupportedParameter {
+                property: "ORGANIZER".to_string(),
+                parameter: "RSVP".to_string()
+            })
+        );
+
+        let mut property = Property::new("DTSTART", "19980119T020000");
+        property.add(TzIDParam::new("America/New_York"));
+        assert_eq!(check_parameters(&property), Ok(()));
+        property.add(Range::ThisAndFuture);
+        assert!(check_parameters(&property).is_err());
+
+        let mut property = Property::new("X-CUSTOM", "anything");
+        property.add(Range::ThisAndFuture);
+        assert_eq!(check_parameters(&property), Ok(()));
+    }
+
+    #[cfg(feature = "rfc7986")]
+    #[test]
+    fn rfc7986_parameters() {
+        use crate::parameters::{AltRep, Language};
+
+        let mut property = Property::new("IMAGE", "https://example.com/image.png");
+        property.add(AltRep::new("https://example.com/image.html"));
+        assert_eq!(check_parameters(&property), Ok(()));
+
+        let mut property = Property::new("CONFERENCE", "https://chat.example.com/audio");
+        property.add(Language::new("de"));
+        assert_eq!(check_parameters(&property), Ok(()));
+
+        let mut property = Property::new("CONFERENCE", "https://chat.example.com/audio");
+        property.add(AltRep::new("https://example.com/dial-in"));
+        assert!(check_parameters(&property).is_err());
+    }
+}
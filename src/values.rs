@@ -0,0 +1,1108 @@
+//! Typed values for properties and parameters.
+//!
+//! Properties and parameters accept text as value. The types of this module
+//! take care of the correct format of some values and can be passed to the
+//! constructors of properties and parameters.
+//!
+//! # Example
+//! ```
+//! use ics::parameters::SentBy;
+//! use ics::properties::Attendee;
+//! use ics::values::CalAddress;
+//!
+//! let address = CalAddress::mailto("jsmith@example.com").unwrap();
+//! assert_eq!(address.as_str(), "mailto:jsmith@example.com");
+//!
+//! let mut attendee = Attendee::new(address);
+//! attendee.add(SentBy::new(CalAddress::mailto("jane_doe@example.com").unwrap()));
+//! ```
+use crate::components::{is_multi_valued, Parameters, Property};
+use crate::parameters::{CUType, FmtType, Language, PartStat, Role, CN, RSVP};
+use crate::properties::{Attendee, RequestStatus};
+use crate::util::{escape_text, unescape_text};
+use crate::validation::{self, Error};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// A calendar user address (`CAL-ADDRESS` value type)
+///
+/// A calendar user address is a URI, usually a `mailto` URI as specified in
+/// [RFC6068](https://tools.ietf.org/html/rfc6068). It can be used as value of
+/// the `ATTENDEE` and `ORGANIZER` properties as well as the `SENT-BY`,
+/// `DELEGATED-TO`, `DELEGATED-FROM` and `MEMBER` parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CalAddress<'a>(Cow<'a, str>);
+
+impl<'a> CalAddress<'a> {
+    /// Creates a calendar user address from a URI. Returns an error if the
+    /// value is not a URI.
+    pub fn new<S>(uri: S) -> Result<Self, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let uri = uri.into();
+        if validation::is_uri(&uri) {
+            Ok(CalAddress(uri))
+        } else {
+            Err(invalid_cal_address())
+        }
+    }
+
+    /// Creates a `mailto` URI from an email address. Characters that are not
+    /// allowed in a URI are percent-encoded. Returns an error if the email
+    /// address is not valid.
+    ///
+    /// # Example
+    /// ```
+    /// use ics::values::CalAddress;
+    ///
+    /// let address = CalAddress::mailto("gorby%kremvax@example.com").unwrap();
+    /// assert_eq!(address.as_str(), "mailto:gorby%25kremvax@example.com");
+    /// assert_eq!(address.email(), Some("gorby%kremvax@example.com".to_string()));
+    ///
+    /// assert!(CalAddress::mailto("jsmith").is_err());
+    /// ```
+    pub fn mailto(email: &str) -> Result<Self, Error> {
+        if !is_email(email) {
+            return Err(invalid_cal_address());
+        }
+        let mut uri = String::with_capacity(email.len() + 7);
+        uri.push_str("mailto:");
+        for &b in email.as_bytes() {
+            if b.is_ascii_alphanumeric() || b"-._~!$'()*+,;:@".contains(&b) {
+                uri.push(b as char);
+            } else {
+                uri.push_str(&format!("%{:02X}", b));
+            }
+        }
+        Ok(CalAddress(Cow::Owned(uri)))
+    }
+
+    /// Returns the email address of a `mailto` URI. Header fields of the URI
+    /// are ignored. For other URIs `None` is returned.
+    pub fn email(&self) -> Option<String> {
+        let scheme = self.0.get(..7)?;
+        if !scheme.eq_ignore_ascii_case("mailto:") {
+            return None;
+        }
+        let address = self.0[7..].split('?').next().unwrap_or("");
+        percent_decode(address).filter(|email| is_email(email))
+    }
+
+    /// Returns the URI of the calendar user address.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CalAddress<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a> From<CalAddress<'a>> for Cow<'a, str> {
+    fn from(address: CalAddress<'a>) -> Self {
+        address.0
+    }
+}
+
+fn invalid_cal_address() -> Error {
+    Error::InvalidFormat {
+        format: "CAL-ADDRESS",
+    }
+}
+
+// Checks the syntax of an email address (addr-spec) without quoted local parts
+// and domain literals. Non-ASCII characters are allowed as specified in
+// RFC6531.
+fn is_email(input: &str) -> bool {
+    let at = match input.rfind('@') {
+        Some(index) => index,
+        None => return false,
+    };
+    let (local, domain) = (&input[..at], &input[at + 1..]);
+    is_dot_atom(local, |c| {
+        c.is_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c)
+    }) && is_dot_atom(domain, |c| c.is_alphanumeric() || c == '-')
+        && domain
+            .split('.')
+            .all(|label| !label.starts_with('-') && !label.ends_with('-'))
+}
+
+fn is_dot_atom<F: Fn(char) -> bool>(input: &str, is_atom_char: F) -> bool {
+    input
+        .split('.')
+        .all(|atom| !atom.is_empty() && atom.chars().all(&is_atom_char))
+}
+
+fn percent_decode(input: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut input = input.bytes();
+    while let Some(b) = input.next() {
+        if b == b'%' {
+            let high = (input.next()? as char).to_digit(16)?;
+            let low = (input.next()? as char).to_digit(16)?;
+            bytes.push((high * 16 + low) as u8);
+        } else {
+            bytes.push(b);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// A media type as specified in [RFC4288](https://tools.ietf.org/html/rfc4288)
+///
+/// A media type consists of a type, a subtype and optional parameters. It is
+/// the value of the `FMTTYPE` parameter.
+///
+/// # Example
+/// ```
+/// use ics::components::Parameter;
+/// use ics::parameters::FmtType;
+/// use ics::values::MediaType;
+///
+/// let media_type = MediaType::new("audio", "basic").unwrap();
+/// assert_eq!(media_type, MediaType::AUDIO_BASIC);
+/// assert_eq!(Parameter::from(FmtType::from(media_type)).to_string(), "FMTTYPE=audio/basic");
+///
+/// let mut media_type = MediaType::TEXT_PLAIN;
+/// media_type.add_parameter("charset", "UTF-8").unwrap();
+/// let fmt_type = FmtType::from(media_type);
+/// assert_eq!(Parameter::from(fmt_type.clone()).to_string(), "FMTTYPE=\"text/plain;charset=UTF-8\"");
+/// assert_eq!(fmt_type.media_type().unwrap().parameter("charset"), Some("UTF-8"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MediaType<'a> {
+    type_: Cow<'a, str>,
+    subtype: Cow<'a, str>,
+    parameters: Cow<'a, [(Cow<'a, str>, Cow<'a, str>)]>,
+}
+
+macro_rules! media_type {
+    ($type:expr, $subtype:expr) => {
+        MediaType {
+            type_: Cow::Borrowed($type),
+            subtype: Cow::Borrowed($subtype),
+            parameters: Cow::Borrowed(&[]),
+        }
+    };
+}
+
+impl<'a> MediaType<'a> {
+    /// `text/plain`
+    pub const TEXT_PLAIN: Self = media_type!("text", "plain");
+
+    /// `text/html`
+    pub const TEXT_HTML: Self = media_type!("text", "html");
+
+    /// `text/calendar`
+    pub const TEXT_CALENDAR: Self = media_type!("text", "calendar");
+
+    /// `image/png`
+    pub const IMAGE_PNG: Self = media_type!("image", "png");
+
+    /// `image/jpeg`
+    pub const IMAGE_JPEG: Self = media_type!("image", "jpeg");
+
+    /// `image/gif`
+    pub const IMAGE_GIF: Self = media_type!("image", "gif");
+
+    /// `audio/basic`
+    pub const AUDIO_BASIC: Self = media_type!("audio", "basic");
+
+    /// `application/pdf`
+    pub const APPLICATION_PDF: Self = media_type!("application", "pdf");
+
+    /// `application/octet-stream`
+    pub const APPLICATION_OCTET_STREAM: Self = media_type!("application", "octet-stream");
+
+    /// Creates a new media type from type and subtype. Returns an error if
+    /// they are not valid names.
+    pub fn new<T, S>(type_: T, subtype: S) -> Result<Self, Error>
+    where
+        T: Into<Cow<'a, str>>,
+        S: Into<Cow<'a, str>>,
+    {
+        let type_ = type_.into();
+        let subtype = subtype.into();
+        if is_reg_name(&type_) && is_reg_name(&subtype) {
+            Ok(MediaType {
+                type_,
+                subtype,
+                parameters: Cow::Borrowed(&[]),
+            })
+        } else {
+            Err(invalid_media_type())
+        }
+    }
+
+    /// Parses a media type like `text/plain;charset=UTF-8`, e.g. the value of
+    /// the `FMTTYPE` parameter.
+    pub fn parse(input: &'a str) -> Result<Self, Error> {
+        let mut parts = split_unquoted(input, ';').into_iter();
+        let mut names = parts.next().unwrap_or("").splitn(2, '/');
+        let type_ = names.next().unwrap_or("").trim();
+        let subtype = names.next().ok_or_else(invalid_media_type)?.trim();
+        let mut media_type = Self::new(type_, subtype)?;
+        for parameter in parts {
+            let mut pair = parameter.splitn(2, '=');
+            let key = pair.next().unwrap_or("").trim();
+            let value = pair.next().ok_or_else(invalid_media_type)?.trim();
+            let value = if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+                Cow::Owned(unescape_quoted_string(&value[1..value.len() - 1]))
+            } else {
+                Cow::Borrowed(value)
+            };
+            media_type.add_parameter(key, value)?;
+        }
+        Ok(media_type)
+    }
+
+    /// Adds a parameter like `charset` to the media type. Returns an error if
+    /// the name is not valid or the value contains control characters.
+    /// Without the `rfc6868` feature, values that must be written as quoted
+    /// strings are rejected as well because the DQUOTE characters cannot be
+    /// part of the `FMTTYPE` parameter.
+    pub fn add_parameter<K, V>(&mut self, key: K, value: V) -> Result<(), Error>
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        let key = key.into();
+        let value = value.into();
+        let is_quoted = value.is_empty() || !is_token(&value);
+        if !is_reg_name(&key)
+            || value.chars().any(char::is_control)
+            || is_quoted && !cfg!(feature = "rfc6868")
+        {
+            return Err(invalid_media_type());
+        }
+        self.parameters.to_mut().push((key, value));
+        Ok(())
+    }
+
+    /// Returns the top-level type, e.g. `text`.
+    pub fn type_(&self) -> &str {
+        &self.type_
+    }
+
+    /// Returns the subtype, e.g. `plain`.
+    pub fn subtype(&self) -> &str {
+        &self.subtype
+    }
+
+    /// Returns the value of a parameter. The name is compared
+    /// case-insensitively.
+    pub fn parameter(&self, key: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_ref())
+    }
+}
+
+impl fmt::Display for MediaType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.type_, self.subtype)?;
+        for (key, value) in self.parameters.iter() {
+            if !value.is_empty() && is_token(value) {
+                write!(f, ";{}={}", key, value)?;
+            } else {
+                write!(f, ";{}=\"", key)?;
+                for c in value.chars() {
+                    if c == '"' || c == '\\' {
+                        f.write_str("\\")?;
+                    }
+                    write!(f, "{}", c)?;
+                }
+                f.write_str("\"")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> From<MediaType<'a>> for FmtType<'a> {
+    fn from(media_type: MediaType<'a>) -> Self {
+        FmtType::new(media_type.to_string())
+    }
+}
+
+fn invalid_media_type() -> Error {
+    Error::InvalidFormat { format: "FMTTYPE" }
+}
+
+// Names of types, subtypes and parameters (reg-name in RFC4288)
+fn is_reg_name(input: &str) -> bool {
+    let starts_with_alphanumeric = input
+        .bytes()
+        .next()
+        .map_or(false, |b| b.is_ascii_alphanumeric());
+    starts_with_alphanumeric
+        && input.len() <= 127
+        && input
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$&.+-^_".contains(&b))
+}
+
+// Parameter values without quotes (token in RFC2045)
+fn is_token(input: &str) -> bool {
+    input
+        .bytes()
+        .all(|b| b.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?=".contains(&b))
+}
+
+// Removes the backslashes of quoted pairs in a quoted string.
+fn unescape_quoted_string(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => output.extend(chars.next()),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+// Splits the input at the separator unless it is inside of quotes.
+fn split_unquoted(input: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut is_quoted = false;
+    let mut start = 0;
+    for (index, c) in input.char_indices() {
+        if c == '"' {
+            is_quoted = !is_quoted;
+        } else if c == separator && !is_quoted {
+            parts.push(&input[start..index]);
+            start = index + 1;
+        }
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
+/// A status code of the `REQUEST-STATUS` property
+///
+/// Status codes consist of a class and a code, optionally with a detail, e.g.
+/// `2.0` or `3.7`. The class specifies whether the request was successful:
+/// - `1.x`: preliminary success
+/// - `2.x`: successful
+/// - `3.x`: client error
+/// - `4.x`: scheduling error
+/// - `5.x`: service error
+///
+/// The codes are specified in [RFC5546 3.6. Status Replies](https://tools.ietf.org/html/rfc5546#section-3.6).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StatusCode {
+    class: u8,
+    code: u8,
+    detail: Option<u8>,
+}
+
+const STATUS_CODES: [(u8, u8, &str); 32] = [
+    (2, 0, "Success."),
+    (
+        2,
+        1,
+        "Success, but fallback taken on one or more property values.",
+    ),
+    (2, 2, "Success; invalid property ignored."),
+    (2, 3, "Success; invalid property parameter ignored."),
+    (2, 4, "Success; unknown, non-standard property ignored."),
+    (
+        2,
+        5,
+        "Success; unknown, non-standard property value ignored.",
+    ),
+    (2, 6, "Success; invalid calendar component ignored."),
+    (2, 7, "Success; request forwarded to Calendar User."),
+    (
+        2,
+        8,
+        "Success; repeating event ignored. Scheduled as a single component.",
+    ),
+    (2, 9, "Success; truncated end date time to date boundary."),
+    (
+        2,
+        10,
+        "Success; repeating VTODO ignored. Scheduled as a single VTODO.",
+    ),
+    (
+        2,
+        11,
+        "Success; unbounded RRULE clipped at some finite number of instances.",
+    ),
+    (3, 0, "Invalid property name."),
+    (3, 1, "Invalid property value."),
+    (3, 2, "Invalid property parameter."),
+    (3, 3, "Invalid property parameter value."),
+    (3, 4, "Invalid calendar component sequence."),
+    (3, 5, "Invalid date or time."),
+    (3, 6, "Invalid rule."),
+    (3, 7, "Invalid Calendar User."),
+    (3, 8, "No authority."),
+    (3, 9, "Unsupported version."),
+    (3, 10, "Request entity too large."),
+    (3, 11, "Required component or property missing."),
+    (3, 12, "Unknown component or property found."),
+    (3, 13, "Unsupported component or property found."),
+    (3, 14, "Unsupported capability."),
+    (4, 0, "Event conflict. Date/time is busy."),
+    (5, 0, "Request not supported."),
+    (5, 1, "Service unavailable."),
+    (5, 2, "Invalid calendar service."),
+    (5, 3, "No scheduling support for user."),
+];
+
+impl StatusCode {
+    /// `2.0` Success
+    pub const SUCCESS: Self = Self::new(2, 0);
+
+    /// `3.1` Invalid property value
+    pub const INVALID_PROPERTY_VALUE: Self = Self::new(3, 1);
+
+    /// `3.7` Invalid Calendar User
+    pub const INVALID_CALENDAR_USER: Self = Self::new(3, 7);
+
+    /// `3.8` No authority
+    pub const NO_AUTHORITY: Self = Self::new(3, 8);
+
+    /// `4.0` Event conflict
+    pub const EVENT_CONFLICT: Self = Self::new(4, 0);
+
+    /// `5.1` Service unavailable
+    pub const SERVICE_UNAVAILABLE: Self = Self::new(5, 1);
+
+    /// Creates a new status code from class and code.
+    pub const fn new(class: u8, code: u8) -> Self {
+        StatusCode {
+            class,
+            code,
+            detail: None,
+        }
+    }
+
+    /// Creates a new status code with a third level, e.g. `2.8.1`.
+    pub const fn with_detail(class: u8, code: u8, detail: u8) -> Self {
+        StatusCode {
+            class,
+            code,
+            detail: Some(detail),
+        }
+    }
+
+    /// Parses a status code like `3.7`.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidFormat {
+            format: "REQUEST-STATUS",
+        };
+        let parse = |part: &str| {
+            if !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()) {
+                part.parse::<u8>().map_err(|_| invalid())
+            } else {
+                Err(invalid())
+            }
+        };
+        let mut parts = input.split('.');
+        let class = parse(parts.next().unwrap_or(""))?;
+        let code = parse(parts.next().ok_or_else(invalid)?)?;
+        let detail = match parts.next() {
+            Some(detail) => Some(parse(detail)?),
+            None => None,
+        };
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(StatusCode {
+            class,
+            code,
+            detail,
+        })
+    }
+
+    /// Returns the class of the status code, e.g. 3 for `3.7`.
+    pub fn class(self) -> u8 {
+        self.class
+    }
+
+    /// Checks whether the request was successful (class 1 or 2).
+    pub fn is_success(self) -> bool {
+        self.class == 1 || self.class == 2
+    }
+
+    /// Checks whether the request failed (class 3, 4 or 5).
+    pub fn is_failure(self) -> bool {
+        self.class >= 3 && self.class <= 5
+    }
+
+    /// Returns the description specified in RFC5546 for the status code.
+    /// Unknown status codes return `None`.
+    pub fn description(self) -> Option<&'static str> {
+        STATUS_CODES
+            .iter()
+            .find(|&&(class, code, _)| class == self.class && code == self.code)
+            .map(|&(_, _, description)| description)
+    }
+}
+
+impl fmt::Display for StatusCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.class, self.code)?;
+        if let Some(detail) = self.detail {
+            write!(f, ".{}", detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of the `REQUEST-STATUS` property
+///
+/// The value consists of a status code, a description and optional data about
+/// the exception, e.g. the name of the property that caused the error.
+///
+/// # Example
+/// ```
+/// use ics::components::Property;
+/// use ics::properties::RequestStatus;
+/// use ics::values::{RequestStatusValue, StatusCode};
+///
+/// let mut status = RequestStatusValue::new(StatusCode::INVALID_PROPERTY_VALUE);
+/// status.data = Some("DTSTART:96-Apr-01".into());
+/// let property = RequestStatus::from(status);
+/// assert_eq!(
+///     Property::from(property.clone()).to_string(),
+///     "REQUEST-STATUS:3.1;Invalid property value.;DTSTART:96-Apr-01\r\n"
+/// );
+///
+/// let status = property.status().unwrap();
+/// assert!(status.code.is_failure());
+/// assert_eq!(status.data.unwrap(), "DTSTART:96-Apr-01");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestStatusValue<'a> {
+    /// Status code
+    pub code: StatusCode,
+    /// Description of the status
+    pub description: Cow<'a, str>,
+    /// Data about the exception
+    pub data: Option<Cow<'a, str>>,
+}
+
+impl<'a> RequestStatusValue<'a> {
+    /// Creates a new value with the description specified in RFC5546 for the
+    /// status code.
+    pub fn new(code: StatusCode) -> Self {
+        RequestStatusValue {
+            code,
+            description: Cow::Borrowed(code.description().unwrap_or("")),
+            data: None,
+        }
+    }
+
+    /// Creates a new value with a custom description.
+    pub fn with_description<S>(code: StatusCode, description: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        RequestStatusValue {
+            code,
+            description: description.into(),
+            data: None,
+        }
+    }
+
+    /// Parses the value of a `REQUEST-STATUS` property. Description and data
+    /// are unescaped.
+    pub fn parse(input: &'a str) -> Result<Self, Error> {
+        let mut parts = split_text(input, ';', 3).into_iter();
+        let code = StatusCode::parse(parts.next().unwrap_or(""))?;
+        let description = parts.next().ok_or(Error::InvalidFormat {
+            format: "REQUEST-STATUS",
+        })?;
+        Ok(RequestStatusValue {
+            code,
+            description: unescape_text(description),
+            data: parts.next().map(unescape_text),
+        })
+    }
+}
+
+impl fmt::Display for RequestStatusValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{};{}",
+            self.code,
+            escape_text(self.description.as_ref())
+        )?;
+        if let Some(data) = &self.data {
+            write!(f, ";{}", escape_text(data.as_ref()))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> From<RequestStatusValue<'a>> for RequestStatus<'a> {
+    fn from(status: RequestStatusValue<'a>) -> Self {
+        RequestStatus::new(status.to_string())
+    }
+}
+
+// Splits text at unescaped separators into at most `limit` parts.
+fn split_text(input: &str, separator: char, limit: usize) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut is_escaped = false;
+    let mut start = 0;
+    for (index, c) in input.char_indices() {
+        if c == separator && !is_escaped && parts.len() + 1 < limit {
+            parts.push(&input[start..index]);
+            start = index + 1;
+        }
+        is_escaped = c == '\\' && !is_escaped;
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
+/// An offset from UTC (`UTC-OFFSET` value type)
+///
+/// The offset is used by the `TZOFFSETFROM` and `TZOFFSETTO` properties of the
+/// [`Standard`](../struct.Standard.html) and
+/// [`Daylight`](../struct.Daylight.html) components. Seconds are only written
+/// if they are not zero and an offset of zero is always written as `+0000`.
+///
+/// # Example
+/// ```
+/// use ics::values::UtcOffset;
+/// use ics::Standard;
+///
+/// let offset = UtcOffset::west(5, 0).unwrap();
+/// assert_eq!(offset.to_string(), "-0500");
+/// assert_eq!(UtcOffset::parse("-0500"), Ok(offset));
+///
+/// let standard = Standard::new("19671029T020000", UtcOffset::west(4, 0).unwrap(), offset);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UtcOffset {
+    seconds: i32,
+}
+
+impl UtcOffset {
+    /// The offset of UTC itself (`+0000`)
+    pub const UTC: Self = UtcOffset { seconds: 0 };
+
+    /// Creates an offset east of UTC (ahead of UTC), e.g. `+0100`.
+    pub fn east(hours: u8, minutes: u8) -> Result<Self, Error> {
+        Self::from_parts(false, hours, minutes, 0)
+    }
+
+    /// Creates an offset west of UTC (behind UTC), e.g. `-0500`.
+    pub fn west(hours: u8, minutes: u8) -> Result<Self, Error> {
+        Self::from_parts(true, hours, minutes, 0)
+    }
+
+    /// Creates an offset from its sign, hours, minutes and seconds. Hours must
+    /// be less than 24, minutes and seconds less than 60.
+    pub fn from_parts(negative: bool, hours: u8, minutes: u8, seconds: u8) -> Result<Self, Error> {
+        if hours >= 24 || minutes >= 60 || seconds >= 60 {
+            return Err(invalid_utc_offset());
+        }
+        let seconds = i32::from(hours) * 3600 + i32::from(minutes) * 60 + i32::from(seconds);
+        Ok(UtcOffset {
+            seconds: if negative { -seconds } else { seconds },
+        })
+    }
+
+    /// Creates an offset from the total number of seconds east of UTC. The
+    /// offset must be less than 24 hours in both directions.
+    pub fn from_seconds(seconds: i32) -> Result<Self, Error> {
+        if seconds > -86400 && seconds < 86400 {
+            Ok(UtcOffset { seconds })
+        } else {
+            Err(invalid_utc_offset())
+        }
+    }
+
+    /// Parses an offset like `+0100`, `-0500` or `+013045`. Negative zero
+    /// (`-0000`) is not allowed.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        if !validation::is_utc_offset(input) {
+            return Err(invalid_utc_offset());
+        }
+        let part =
+            |range: std::ops::Range<usize>| input.get(range).map_or(0, |s| s.parse().unwrap_or(0));
+        Self::from_parts(input.starts_with('-'), part(1..3), part(3..5), part(5..7))
+    }
+
+    /// Returns the total number of seconds east of UTC.
+    pub fn as_seconds(self) -> i32 {
+        self.seconds
+    }
+
+    /// Checks whether the offset is west of UTC.
+    pub fn is_negative(self) -> bool {
+        self.seconds < 0
+    }
+
+    /// Returns the hours of the offset without sign.
+    pub fn hours(self) -> u8 {
+        (self.seconds.abs() / 3600) as u8
+    }
+
+    /// Returns the minutes of the offset.
+    pub fn minutes(self) -> u8 {
+        (self.seconds.abs() / 60 % 60) as u8
+    }
+
+    /// Returns the seconds of the offset.
+    pub fn seconds(self) -> u8 {
+        (self.seconds.abs() % 60) as u8
+    }
+}
+
+impl fmt::Display for UtcOffset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.is_negative() { '-' } else { '+' };
+        write!(f, "{}{:02}{:02}", sign, self.hours(), self.minutes())?;
+        if self.seconds() != 0 {
+            write!(f, "{:02}", self.seconds())?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> From<UtcOffset> for Cow<'a, str> {
+    fn from(offset: UtcOffset) -> Self {
+        Cow::Owned(offset.to_string())
+    }
+}
+
+fn invalid_utc_offset() -> Error {
+    Error::InvalidFormat {
+        format: "UTC-OFFSET",
+    }
+}
+
+/// An attendee of a calendar component
+///
+/// A [`Participant`] combines the calendar user address with the parameters
+/// that describe the participation of the attendee. It can be converted into
+/// an [`Attendee`] property and back which allows reading and updating the
+/// participation status without touching the parameters directly.
+///
+/// # Example
+/// ```
+/// use ics::components::Property;
+/// use ics::parameters::{PartStat, Role, RSVP};
+/// use ics::properties::Attendee;
+/// use ics::values::Participant;
+///
+/// let mut participant = Participant::new("mailto:js@example.com");
+/// participant.name = Some("John Smith".into());
+/// participant.role = Some(Role::REQ_PARTICIPANT);
+/// participant.rsvp = Some(RSVP::True);
+///
+/// let attendee = Attendee::from(participant);
+/// assert_eq!(
+///     Property::from(attendee.clone()).to_string(),
+///     "ATTENDEE;CN=John Smith;ROLE=REQ-PARTICIPANT;RSVP=TRUE:mailto:js@example.com\r\n"
+/// );
+///
+/// // Update the participation status of the attendee.
+/// let mut participant = Participant::from(attendee);
+/// assert_eq!(participant.status, None);
+/// participant.status = Some(PartStat::ACCEPTED);
+/// participant.rsvp = None;
+/// assert_eq!(
+///     Property::from(Attendee::from(participant)).to_string(),
+///     "ATTENDEE;CN=John Smith;PARTSTAT=ACCEPTED;ROLE=REQ-PARTICIPANT:mailto:js@exa\r\n mple.com\r\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Participant<'a> {
+    /// Calendar user address of the attendee
+    pub address: Cow<'a, str>,
+    /// Common name (`CN` parameter)
+    pub name: Option<Cow<'a, str>>,
+    /// Calendar user type (`CUTYPE` parameter)
+    pub user_type: Option<CUType<'a>>,
+    /// Participation role (`ROLE` parameter)
+    pub role: Option<Role<'a>>,
+    /// Participation status (`PARTSTAT` parameter)
+    pub status: Option<PartStat<'a>>,
+    /// Expectation of a reply (`RSVP` parameter)
+    pub rsvp: Option<RSVP>,
+    /// Language of the common name (`LANGUAGE` parameter)
+    pub language: Option<Language<'a>>,
+    /// Email address if it differs from the calendar user address (`EMAIL`
+    /// parameter)
+    #[cfg(feature = "rfc7986")]
+    pub email: Option<Cow<'a, str>>,
+    /// Other parameters of the attendee, e.g. `DELEGATED-TO` or `SENT-BY`
+    pub parameters: Parameters<'a>,
+}
+
+impl<'a> Participant<'a> {
+    /// Creates a new attendee with the given calendar user address.
+    pub fn new<S>(address: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Participant {
+            address: address.into(),
+            name: None,
+            user_type: None,
+            role: None,
+            status: None,
+            rsvp: None,
+            language: None,
+            #[cfg(feature = "rfc7986")]
+            email: None,
+            parameters: Parameters::new(),
+        }
+    }
+}
+
+impl<'a> From<Participant<'a>> for Attendee<'a> {
+    fn from(participant: Participant<'a>) -> Self {
+        let mut attendee = Attendee::new(participant.address);
+        attendee.append(participant.parameters);
+        if let Some(name) = participant.name {
+            attendee.add(CN::new(name));
+        }
+        if let Some(user_type) = participant.user_type {
+            attendee.add(user_type);
+        }
+        if let Some(role) = participant.role {
+            attendee.add(role);
+        }
+        if let Some(status) = participant.status {
+            attendee.add(status);
+        }
+        if let Some(rsvp) = participant.rsvp {
+            attendee.add(rsvp);
+        }
+        if let Some(language) = participant.language {
+            attendee.add(language);
+        }
+        #[cfg(feature = "rfc7986")]
+        {
+            if let Some(email) = participant.email {
+                attendee.add(crate::parameters::Email::new(email));
+            }
+        }
+        attendee
+    }
+}
+
+impl<'a> From<Participant<'a>> for Property<'a> {
+    fn from(participant: Participant<'a>) -> Self {
+        Attendee::from(participant).into()
+    }
+}
+
+impl<'a> From<Attendee<'a>> for Participant<'a> {
+    fn from(attendee: Attendee<'a>) -> Self {
+        let property = Property::from(attendee);
+        let mut participant = Participant::new(property.value);
+        for (key, value) in property.parameters {
+            if is_multi_valued(&key) {
+                participant.parameters.insert(key, value);
+                continue;
+            }
+            match key.to_ascii_uppercase().as_str() {
+                "CN" => participant.name = Some(value),
+                "CUTYPE" => participant.user_type = Some(CUType::new(value)),
+                "ROLE" => participant.role = Some(Role::new(value)),
+                "PARTSTAT" => participant.status = Some(PartStat::new(value)),
+                "RSVP" if value.eq_ignore_ascii_case("TRUE") => participant.rsvp = Some(RSVP::True),
+                "RSVP" if value.eq_ignore_ascii_case("FALSE") => {
+                    participant.rsvp = Some(RSVP::False)
+                }
+                "LANGUAGE" => participant.language = Some(Language::new(value)),
+                #[cfg(feature = "rfc7986")]
+                "EMAIL" => participant.email = Some(value),
+                _ => {
+                    participant.parameters.insert(key, value);
+                }
+            }
+        }
+        participant
+    }
+}
+
+impl<'a> TryFrom<Property<'a>> for Participant<'a> {
+    type Error = Error;
+
+    /// Parses an `ATTENDEE` property. Other properties return an error.
+    fn try_from(property: Property<'a>) -> Result<Self, Self::Error> {
+        if !property.key.eq_ignore_ascii_case("ATTENDEE") {
+            return Err(Error::InvalidFormat { format: "ATTENDEE" });
+        }
+        let mut attendee = Attendee::new(property.value);
+        attendee.append(property.parameters);
+        Ok(Participant::from(attendee))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_email, CalAddress};
+
+    #[test]
+    fn email() {
+        assert!(is_email("john.doe@example.com"));
+        assert!(is_email("jüri@bücher.example"));
+        assert!(!is_email("john..doe@example.com"));
+        assert!(!is_email("john@-example.com"));
+        assert!(!is_email("@example.com"));
+        assert!(!is_email("john doe@example.com"));
+    }
+
+    #[test]
+    fn mailto() {
+        let address = CalAddress::mailto("jüri?x@example.com").unwrap();
+        assert_eq!(address.as_str(), "mailto:j%C3%BCri%3Fx@example.com");
+        assert_eq!(address.email(), Some("jüri?x@example.com".to_string()));
+        assert!(CalAddress::new("jsmith@example.com").is_err());
+        assert_eq!(CalAddress::new("urn:uuid:4e8ee8b0").unwrap().email(), None);
+        assert_eq!(
+            CalAddress::new("MAILTO:jsmith@example.com?subject=Hi")
+                .unwrap()
+                .email(),
+            Some("jsmith@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn participant() {
+        use super::Participant;
+        use crate::components::{Parameter, Property};
+        use crate::parameters::{PartStat, SentBy};
+        use std::convert::TryFrom;
+
+        let mut property = Property::new("ATTENDEE", "mailto:jsmith@example.com");
+        property.add(PartStat::TENTATIVE);
+        property.add(SentBy::new("mailto:sray@example.com"));
+        property.add(crate::parameters::CN::new("Smith, \"John\""));
+        let mut participant = Participant::try_from(property.clone()).unwrap();
+        assert_eq!(participant.name, Some("Smith, \"John\"".into()));
+        assert_eq!(participant.status, Some(PartStat::TENTATIVE));
+        assert_eq!(participant.parameters.len(), 1);
+
+        participant.status = Some(PartStat::DECLINED);
+        property.add(PartStat::DECLINED);
+        assert_eq!(Property::from(participant), property);
+        assert!(Participant::try_from(Property::new("ORGANIZER", "mailto:a@example.com")).is_err());
+
+        let mut property = Property::new("ATTENDEE", "mailto:jsmith@example.com");
+        property.add(crate::parameters::CN::new("Smiley ^^ face"));
+        let participant = Participant::try_from(property).unwrap();
+        assert_eq!(participant.name, Some("Smiley ^^ face".into()));
+
+        let mut property = Property::new("ATTENDEE", "mailto:jsmith@example.com");
+        property.add(Parameter::new("cn", "John Smith"));
+        property.add(Parameter::new("partstat", "ACCEPTED"));
+        property.add(crate::parameters::DelegatedTo::new("mailto:a@example.com"));
+        let participant = Participant::try_from(property).unwrap();
+        assert_eq!(participant.name, Some("John Smith".into()));
+        assert_eq!(participant.status, Some(PartStat::ACCEPTED));
+        assert_eq!(
+            participant
+                .parameters
+                .get("DELEGATED-TO")
+                .map(AsRef::as_ref),
+            Some("\"mailto:a@example.com\"")
+        );
+    }
+
+    #[test]
+    fn media_type() {
+        use super::MediaType;
+
+        let media_type = MediaType::parse("text/plain; charset=\"us-ascii\"").unwrap();
+        assert_eq!(media_type.type_(), "text");
+        assert_eq!(media_type.subtype(), "plain");
+        assert_eq!(media_type.parameter("CHARSET"), Some("us-ascii"));
+        assert_eq!(media_type.to_string(), "text/plain;charset=us-ascii");
+        assert!(MediaType::parse("text").is_err());
+        assert!(MediaType::parse("text/plain;charset").is_err());
+        assert!(MediaType::new("text", "pl@in").is_err());
+        assert!(MediaType::new("", "plain").is_err());
+    }
+
+    #[cfg(feature = "rfc6868")]
+    #[test]
+    fn quoted_media_type() {
+        use super::MediaType;
+        use crate::components::Parameter;
+        use crate::parameters::FmtType;
+
+        let input = "text/plain;format=\"a \\\"b\\\"\"";
+        let media_type = MediaType::parse(input).unwrap();
+        assert_eq!(media_type.parameter("format"), Some("a \"b\""));
+        assert_eq!(media_type.to_string(), input);
+
+        let parameter = Parameter::from(FmtType::from(media_type)).to_string();
+        assert_eq!(parameter, "FMTTYPE=\"text/plain;format=^'a \\^'b\\^'^'\"");
+        let parameter = Parameter::parse(&parameter).unwrap();
+        assert_eq!(parameter.value(), input);
+    }
+
+    #[cfg(not(feature = "rfc6868"))]
+    #[test]
+    fn quoted_media_type() {
+        use super::MediaType;
+
+        assert!(MediaType::parse("text/plain;format=\"a \\\"b\\\"\"").is_err());
+        let mut media_type = MediaType::TEXT_PLAIN;
+        assert!(media_type.add_parameter("format", "").is_err());
+    }
+
+    #[test]
+    fn request_status() {
+        use super::{RequestStatusValue, StatusCode};
+
+        assert_eq!(
+            StatusCode::parse("2.8.1"),
+            Ok(StatusCode::with_detail(2, 8, 1))
+        );
+        assert!(StatusCode::parse("2").is_err());
+        assert!(StatusCode::parse("2.x").is_err());
+        assert!(StatusCode::parse("2.1.1.1").is_err());
+        assert_eq!(
+            StatusCode::new(3, 14).description(),
+            Some("Unsupported capability.")
+        );
+        assert!(StatusCode::new(1, 0).is_success());
+
+        let status = RequestStatusValue::with_description(StatusCode::new(2, 0), "Success; done");
+        assert_eq!(status.to_string(), "2.0;Success\\; done");
+        assert_eq!(RequestStatusValue::parse("2.0;Success\\; done"), Ok(status));
+        let status = RequestStatusValue::parse("4.0;Event conflict.;DTSTART:19970101;x;y").unwrap();
+        assert_eq!(status.data, Some("DTSTART:19970101;x;y".into()));
+    }
+
+    #[test]
+    fn utc_offset() {
+        use super::UtcOffset;
+
+        assert_eq!(UtcOffset::west(0, 0), Ok(UtcOffset::UTC));
+        assert_eq!(UtcOffset::UTC.to_string(), "+0000");
+        assert_eq!(UtcOffset::east(5, 30).unwrap().to_string(), "+0530");
+        assert_eq!(UtcOffset::from_seconds(-1800).unwrap().to_string(), "-0030");
+        assert_eq!(
+            UtcOffset::from_parts(false, 1, 30, 45).unwrap().to_string(),
+            "+013045"
+        );
+        assert_eq!(UtcOffset::parse("+013045").unwrap().as_seconds(), 5445);
+        assert!(UtcOffset::east(24, 0).is_err());
+        assert!(UtcOffset::from_seconds(86400).is_err());
+        assert!(UtcOffset::parse("-0000").is_err());
+        assert!(UtcOffset::parse("+1").is_err());
+        assert!(UtcOffset::parse("0100").is_err());
+    }
+}
@@ -0,0 +1,541 @@
+//! xCal, the XML format for iCalendar.
+//!
+//! xCal is specified in [RFC6321](https://tools.ietf.org/html/rfc6321). The
+//! components are wrapped in an `icalendar` element of the
+//! `urn:ietf:params:xml:ns:icalendar-2.0` namespace. Each component has a
+//! `properties` and a `components` element for its properties and
+//! sub-components. Each property contains a `parameters` element and its
+//! values wrapped in elements named after their value type.
+//!
+//! Values are converted the same way as for [`jcal`](../jcal/index.html),
+//! e.g. dates are written as `2019-10-06`. Recurrence rules, periods and the
+//! values of `GEO` and `REQUEST-STATUS` are written as structured elements.
+//!
+//! # Example
+//! ```
+//! use ics::properties::{Action, Trigger};
+//! use ics::{Alarm, ICalendar};
+//!
+//! let mut calendar = ICalendar::new("2.0", "-//Example Inc.//Example Calendar//EN");
+//! calendar.add_component(Alarm::new(Action::audio(), Trigger::new("-PT15M")));
+//!
+//! let xcal = calendar.to_xcal();
+//! assert_eq!(
+//!     xcal,
+//!     concat!(
+//!         r#"<?xml version="1.0" encoding="utf-8"?>"#,
+//!         r#"<icalendar xmlns="urn:ietf:params:xml:ns:icalendar-2.0"><vcalendar><properties>"#,
+//!         r#"<version><text>2.0</text></version>"#,
+//!         r#"<prodid><text>-//Example Inc.//Example Calendar//EN</text></prodid>"#,
+//!         r#"</properties><components><valarm><properties>"#,
+//!         r#"<action><text>AUDIO</text></action>"#,
+//!         r#"<trigger><duration>-PT15M</duration></trigger>"#,
+//!         r#"</properties></valarm></components></vcalendar></icalendar>"#
+//!     )
+//! );
+//! assert_eq!(ICalendar::from_xcal(&xcal).unwrap(), calendar);
+//! ```
+use crate::components::Component;
+use crate::jcal;
+use crate::json::Json;
+use crate::xml::{self, Element, Escape};
+use std::borrow::Cow;
+use std::error;
+use std::fmt;
+use std::io::{self, Write};
+
+/// The XML namespace of xCal
+pub const NAMESPACE: &str = "urn:ietf:params:xml:ns:icalendar-2.0";
+
+/// Converts a component with its properties and sub-components into an xCal
+/// document.
+pub fn to_string(component: &Component) -> String {
+    let mut output = String::new();
+    write_document(&mut output, component).expect("writing to a string cannot fail");
+    output
+}
+
+/// Writes a component with its properties and sub-components as xCal
+/// document to a writer.
+pub fn write<W>(component: &Component, mut writer: W) -> io::Result<()>
+where
+    W: Write,
+{
+    writer.write_all(to_string(component).as_bytes())
+}
+
+fn write_document<W: fmt::Write>(writer: &mut W, component: &Component) -> fmt::Result {
+    write!(
+        writer,
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?><icalendar xmlns=\"{}\">",
+        NAMESPACE
+    )?;
+    write_component(writer, &jcal::component_to_json(component))?;
+    write!(writer, "</icalendar>")
+}
+
+// Parameters whose values are not text
+const PARAMETER_TYPES: [(&str, &str); 7] = [
+    ("altrep", "uri"),
+    ("delegated-from", "cal-address"),
+    ("delegated-to", "cal-address"),
+    ("dir", "uri"),
+    ("member", "cal-address"),
+    ("rsvp", "boolean"),
+    ("sent-by", "cal-address"),
+];
+
+// The elements of the structured values of GEO and REQUEST-STATUS
+const GEO: [&str; 2] = ["latitude", "longitude"];
+const REQUEST_STATUS: [&str; 3] = ["code", "description", "data"];
+
+// Writes a component from its jCal representation.
+fn write_component<W: fmt::Write>(writer: &mut W, component: &Json) -> fmt::Result {
+    let (name, properties, components) = match component {
+        Json::Array(parts) => match parts.as_slice() {
+            [Json::String(name), Json::Array(properties), Json::Array(components)] => {
+                (name, properties, components)
+            }
+            _ => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+    write!(writer, "<{}><properties>", name)?;
+    for property in properties {
+        write_property(writer, property)?;
+    }
+    write!(writer, "</properties>")?;
+    if !components.is_empty() {
+        write!(writer, "<components>")?;
+        for component in components {
+            write_component(writer, component)?;
+        }
+        write!(writer, "</components>")?;
+    }
+    write!(writer, "</{}>", name)
+}
+
+// Writes a property from its jCal representation.
+fn write_property<W: fmt::Write>(writer: &mut W, property: &Json) -> fmt::Result {
+    let (name, parameters, value_type, values) = match property {
+        Json::Array(parts) if parts.len() >= 4 => match (&parts[0], &parts[1], &parts[2]) {
+            (Json::String(name), Json::Object(parameters), Json::String(value_type)) => {
+                (name, parameters, value_type, &parts[3..])
+            }
+            _ => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+    write!(writer, "<{}>", name)?;
+    if !parameters.is_empty() {
+        write!(writer, "<parameters>")?;
+        for (key, value) in parameters {
+            let value_type = PARAMETER_TYPES
+                .iter()
+                .find(|(parameter, _)| parameter == key)
+                .map_or("text", |&(_, value_type)| value_type);
+            write!(writer, "<{}>", key)?;
+            let values = match value {
+                Json::Array(values) => values.as_slice(),
+                value => std::slice::from_ref(value),
+            };
+            for value in values {
+                if let Json::String(value) = value {
+                    let value = if value_type == "boolean" {
+                        Cow::Owned(value.to_ascii_lowercase())
+                    } else {
+                        Cow::Borrowed(value.as_ref())
+                    };
+                    write_element(writer, value_type, &value)?;
+                }
+            }
+            write!(writer, "</{}>", key)?;
+        }
+        write!(writer, "</parameters>")?;
+    }
+    for value in values {
+        match value {
+            Json::Array(parts) if name == "geo" || name == "request-status" => {
+                let names: &[&str] = if name == "geo" { &GEO } else { &REQUEST_STATUS };
+                for (name, part) in names.iter().zip(parts) {
+                    write_value(writer, name, part)?;
+                }
+            }
+            Json::String(value) if value_type == "period" => {
+                write!(writer, "<period>")?;
+                match value.find('/') {
+                    Some(index) => {
+                        let end = &value[index + 1..];
+                        let end_type =
+                            if end.starts_with('P') || end.starts_with('-') || end.starts_with('+')
+                            {
+                                "duration"
+                            } else {
+                                "end"
+                            };
+                        write_element(writer, "start", &value[..index])?;
+                        write_element(writer, end_type, end)?;
+                    }
+                    None => write_element(writer, "start", value)?,
+                }
+                write!(writer, "</period>")?;
+            }
+            Json::Object(members) => {
+                write!(writer, "<{}>", value_type)?;
+                for (key, value) in members {
+                    match value {
+                        Json::Array(values) => {
+                            for value in values {
+                                write_value(writer, key, value)?;
+                            }
+                        }
+                        value => write_value(writer, key, value)?,
+                    }
+                }
+                write!(writer, "</{}>", value_type)?;
+            }
+            value => write_value(writer, value_type, value)?,
+        }
+    }
+    write!(writer, "</{}>", name)
+}
+
+fn write_value<W: fmt::Write>(writer: &mut W, name: &str, value: &Json) -> fmt::Result {
+    match value {
+        Json::Null => write_element(writer, name, ""),
+        Json::Bool(value) => write_element(writer, name, if *value { "true" } else { "false" }),
+        Json::Number(value) | Json::String(value) => write_element(writer, name, value),
+        Json::Array(values) => {
+            for value in values {
+                write_value(writer, name, value)?;
+            }
+            Ok(())
+        }
+        Json::Object(_) => Ok(()),
+    }
+}
+
+fn write_element<W: fmt::Write>(writer: &mut W, name: &str, text: &str) -> fmt::Result {
+    write!(writer, "<{0}>{1}</{0}>", name, Escape(text))
+}
+
+/// The error type for reading xCal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// The input is not valid XML.
+    Syntax {
+        /// Byte offset of the invalid input
+        offset: usize,
+    },
+    /// The XML does not have the structure of xCal.
+    InvalidStructure {
+        /// Description of the expected element, e.g. `property`
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Syntax { offset } => write!(f, "invalid XML at byte {}", offset),
+            Error::InvalidStructure { expected } => {
+                write!(f, "invalid xCal: expected {}", expected)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<jcal::Error> for Error {
+    fn from(error: jcal::Error) -> Self {
+        match error {
+            jcal::Error::Syntax { offset } => Error::Syntax { offset },
+            jcal::Error::InvalidStructure { expected } => Error::InvalidStructure { expected },
+        }
+    }
+}
+
+/// Parses an xCal document into a component with its properties and
+/// sub-components. The `icalendar` root element must be of the xCal namespace
+/// and contain exactly one component. Elements of other namespaces are
+/// ignored.
+pub fn from_str(input: &str) -> Result<Component<'_>, Error> {
+    let mut root = xml::parse(input).map_err(|offset| Error::Syntax { offset })?;
+    if root.name != "icalendar" || !is_xcal(&root) {
+        return Err(Error::InvalidStructure {
+            expected: "icalendar element of the xCal namespace",
+        });
+    }
+    retain_xcal(&mut root);
+    if root.children.len() != 1 {
+        return Err(Error::InvalidStructure {
+            expected: "icalendar element with one component",
+        });
+    }
+    let component = component_from_xml(root.children.into_iter().next().unwrap());
+    Ok(jcal::component_from_json(component)?)
+}
+
+fn is_xcal(element: &Element<'_>) -> bool {
+    element
+        .namespace
+        .as_ref()
+        .map_or(false, |namespace| namespace == NAMESPACE)
+}
+
+// Removes the elements of other namespaces, e.g. extensions, which are
+// ignored.
+fn retain_xcal(element: &mut Element<'_>) {
+    element.children.retain(is_xcal);
+    for child in &mut element.children {
+        retain_xcal(child);
+    }
+}
+
+// Converts a component element into its jCal representation.
+fn component_from_xml(element: Element<'_>) -> Json<'_> {
+    let mut properties = Vec::new();
+    let mut components = Vec::new();
+    for child in element.children {
+        match child.name {
+            "properties" => properties.extend(child.children.into_iter().map(property_from_xml)),
+            "components" => components.extend(child.children.into_iter().map(component_from_xml)),
+            _ => (),
+        }
+    }
+    Json::Array(vec![
+        Json::String(Cow::Borrowed(element.name)),
+        Json::Array(properties),
+        Json::Array(components),
+    ])
+}
+
+// Converts a property element into its jCal representation.
+fn property_from_xml(element: Element<'_>) -> Json<'_> {
+    let name = element.name;
+    let mut parameters = Vec::new();
+    let mut value_type = "unknown";
+    let mut values = Vec::new();
+    let mut structured = Vec::new();
+    for child in element.children {
+        match child.name {
+            "parameters" => {
+                for parameter in child.children {
+                    let mut parameter_values: Vec<_> = parameter
+                        .children
+                        .into_iter()
+                        .map(|value| {
+                            let text = if value.name == "boolean" {
+                                Cow::Owned(value.text.to_ascii_uppercase())
+                            } else {
+                                value.text
+                            };
+                            Json::String(text)
+                        })
+                        .collect();
+                    let value = if parameter_values.len() == 1 {
+                        parameter_values.remove(0)
+                    } else {
+                        Json::Array(parameter_values)
+                    };
+                    parameters.push((Cow::Borrowed(parameter.name), value));
+                }
+            }
+            part if (name == "geo" && GEO.contains(&part))
+                || (name == "request-status" && REQUEST_STATUS.contains(&part)) =>
+            {
+                structured.push(Json::String(child.text));
+            }
+            part => {
+                value_type = part;
+                values.push(value_from_xml(child));
+            }
+        }
+    }
+    if !structured.is_empty() {
+        value_type = if name == "geo" { "float" } else { "text" };
+        values.push(Json::Array(structured));
+    }
+    let mut json = vec![
+        Json::String(Cow::Borrowed(name)),
+        Json::Object(parameters),
+        Json::String(Cow::Borrowed(value_type)),
+    ];
+    json.extend(values);
+    Json::Array(json)
+}
+
+fn value_from_xml(element: Element<'_>) -> Json<'_> {
+    match element.name {
+        "boolean" => Json::Bool(element.text.trim().eq_ignore_ascii_case("true")),
+        "period" => {
+            let mut period = String::new();
+            for part in &element.children {
+                if part.name != "start" {
+                    period.push('/');
+                }
+                period.push_str(&part.text);
+            }
+            Json::String(Cow::Owned(period))
+        }
+        "recur" => {
+            let mut members: Vec<(Cow<str>, Json)> = Vec::new();
+            for part in element.children {
+                let name = part.name;
+                let value = Json::String(part.text);
+                match members.iter_mut().find(|(key, _)| key == name) {
+                    Some((_, Json::Array(values))) => values.push(value),
+                    Some((_, existing)) => {
+                        let first = std::mem::replace(existing, Json::Null);
+                        *existing = Json::Array(vec![first, value]);
+                    }
+                    None => members.push((Cow::Borrowed(name), value)),
+                }
+            }
+            Json::Object(members)
+        }
+        _ => Json::String(element.text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_str, to_string, Error};
+    use crate::components::{Component, Property};
+    use crate::parameters::{DelegatedTo, TzIDParam, Value, RSVP};
+
+    fn property(property: Property) -> String {
+        let mut component = Component::new("X");
+        component.add_property(property);
+        let xcal = to_string(&component);
+        let start = xcal.find("<x><properties>").unwrap() + "<x><properties>".len();
+        let end = xcal.find("</properties></x>").unwrap();
+        xcal[start..end].to_string()
+    }
+
+    #[test]
+    fn values() {
+        let mut dtend = Property::new("DTEND", "20190101T100000");
+        dtend.add(TzIDParam::new("Europe/Berlin"));
+        assert_eq!(
+            property(dtend),
+            "<dtend><parameters><tzid><text>Europe/Berlin</text></tzid></parameters><date-time>2019-01-01T10:00:00</date-time></dtend>"
+        );
+        assert_eq!(
+            property(Property::new("CATEGORIES", "A&B,C")),
+            "<categories><text>A&amp;B</text><text>C</text></categories>"
+        );
+        assert_eq!(
+            property(Property::new("GEO", "37.386013;-122.082932")),
+            "<geo><latitude>37.386013</latitude><longitude>-122.082932</longitude></geo>"
+        );
+        assert_eq!(
+            property(Property::new("REQUEST-STATUS", "2.0;Success")),
+            "<request-status><code>2.0</code><description>Success</description></request-status>"
+        );
+        assert_eq!(
+            property(Property::new("FREEBUSY", "19970308T160000Z/PT3H")),
+            "<freebusy><period><start>1997-03-08T16:00:00Z</start><duration>PT3H</duration></period></freebusy>"
+        );
+        assert_eq!(
+            property(Property::new("RRULE", "FREQ=WEEKLY;BYDAY=MO,TU")),
+            "<rrule><recur><freq>WEEKLY</freq><byday>MO</byday><byday>TU</byday></recur></rrule>"
+        );
+        assert_eq!(
+            property(Property::boolean("X-FLAG", false)),
+            "<x-flag><boolean>false</boolean></x-flag>"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut component = Component::new("VCALENDAR");
+        let mut event = Component::new("VEVENT");
+        let mut dtstart = Property::new("DTSTART", "20190101");
+        dtstart.add(Value::DATE);
+        event.add_property(dtstart);
+        let mut attendee = Property::new("ATTENDEE", "mailto:a@example.com");
+        attendee.add(DelegatedTo::new("mailto:b@example.com"));
+        attendee.add(DelegatedTo::new("mailto:c@example.com"));
+        attendee.add(RSVP::True);
+        event.add_property(attendee);
+        for (key, value) in &[
+            ("SUMMARY", "<a>\\, b\\; c\\nd"),
+            ("CATEGORIES", "A\\,B,C"),
+            ("GEO", "37.386013;-122.082932"),
+            ("REQUEST-STATUS", "3.1;Invalid\\; value;DTSTART:96-Apr-01"),
+            (
+                "FREEBUSY",
+                "19970308T160000Z/PT3H,19970308T200000Z/19970308T210000Z",
+            ),
+            (
+                "RRULE",
+                "FREQ=YEARLY;UNTIL=20000101T000000Z;BYDAY=-1SU,2MO;BYMONTH=10",
+            ),
+            ("X-CUSTOM", "a\\,b"),
+        ] {
+            event.add_property(Property::new(*key, *value));
+        }
+        event.add_property(Property::boolean("X-FLAG", true));
+        let mut timezone = Component::new("VTIMEZONE");
+        let mut standard = Component::new("STANDARD");
+        standard.add_property(Property::new("TZOFFSETFROM", "-0400"));
+        timezone.add_component(standard);
+        component.add_component(event);
+        component.add_component(timezone);
+
+        let xcal = to_string(&component);
+        assert_eq!(from_str(&xcal), Ok(component));
+    }
+
+    #[test]
+    fn syntax_error() {
+        assert!(from_str("<icalendar>").is_err());
+    }
+
+    #[test]
+    fn missing_component() {
+        assert_eq!(
+            from_str(r#"<icalendar xmlns="urn:ietf:params:xml:ns:icalendar-2.0"/>"#),
+            Err(Error::InvalidStructure {
+                expected: "icalendar element with one component"
+            })
+        );
+    }
+
+    #[test]
+    fn property_without_value() {
+        assert!(from_str(
+            r#"<icalendar xmlns="urn:ietf:params:xml:ns:icalendar-2.0"><vevent><properties><summary/></properties></vevent></icalendar>"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn namespaces() {
+        let expected = Error::InvalidStructure {
+            expected: "icalendar element of the xCal namespace",
+        };
+        assert_eq!(
+            from_str("<icalendar><vevent/></icalendar>"),
+            Err(expected.clone())
+        );
+        assert_eq!(
+            from_str(r#"<icalendar xmlns="urn:example"><vevent/></icalendar>"#),
+            Err(expected)
+        );
+
+        let component = from_str(concat!(
+            r#"<x:icalendar xmlns:x="urn:ietf:params:xml:ns:icalendar-2.0" xmlns:e="urn:example">"#,
+            r#"<x:vevent><x:properties><e:color><x:text>red</x:text></e:color>"#,
+            r#"<x:summary><x:text>Meeting</x:text></x:summary></x:properties>"#,
+            r#"</x:vevent><e:extension/></x:icalendar>"#
+        ))
+        .unwrap();
+        assert_eq!(
+            component.to_string(),
+            "BEGIN:VEVENT\r\nSUMMARY:Meeting\r\nEND:VEVENT\r\n"
+        );
+    }
+}
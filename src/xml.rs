@@ -0,0 +1,324 @@
+//! Minimal XML writer and parser used by the xCal format.
+//!
+//! The parser only supports what is needed to read xCal documents: elements
+//! with their namespace, attributes (which are skipped apart from namespace
+//! declarations), character data with the predefined and numeric entities,
+//! CDATA sections, comments and processing instructions. Document type
+//! declarations are rejected.
+use std::borrow::Cow;
+use std::fmt;
+
+/// An XML element with its child elements and character data
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Element<'a> {
+    /// Local name of the element without namespace prefix
+    pub(crate) name: &'a str,
+    /// Namespace of the element if it has one
+    pub(crate) namespace: Option<Cow<'a, str>>,
+    pub(crate) children: Vec<Element<'a>>,
+    pub(crate) text: Cow<'a, str>,
+}
+
+/// Escapes character data and attribute values. Control characters that are
+/// not allowed in XML 1.0 are replaced with U+FFFD.
+pub(crate) struct Escape<'a>(pub(crate) &'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut start = 0;
+        for (index, c) in self.0.char_indices() {
+            let escaped = match c {
+                '&' => "&amp;",
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '"' => "&quot;",
+                // Carriage returns would be normalized to line feeds.
+                '\r' => "&#13;",
+                '\t' | '\n' => continue,
+                '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => "\u{fffd}",
+                _ => continue,
+            };
+            f.write_str(&self.0[start..index])?;
+            f.write_str(escaped)?;
+            start = index + c.len_utf8();
+        }
+        f.write_str(&self.0[start..])
+    }
+}
+
+// Nesting deeper than this is rejected to avoid overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Parses an XML document and returns its root element. On error, the byte
+/// offset of the invalid input is returned.
+pub(crate) fn parse(input: &str) -> Result<Element<'_>, usize> {
+    let mut parser = Parser { input, offset: 0 };
+    parser.skip_misc()?;
+    let root = parser.element(0, &[])?;
+    parser.skip_misc()?;
+    if parser.offset == input.len() {
+        Ok(root)
+    } else {
+        Err(parser.offset)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<&'a str, usize> {
+        let rest = self.rest();
+        match rest.find(end) {
+            Some(index) => {
+                self.offset += index + end.len();
+                Ok(&rest[..index])
+            }
+            None => Err(self.input.len()),
+        }
+    }
+
+    // Skips whitespace, comments and processing instructions (including the
+    // XML declaration).
+    fn skip_misc(&mut self) -> Result<(), usize> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, usize> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>' || c == '=')
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.offset);
+        }
+        self.offset += len;
+        Ok(&rest[..len])
+    }
+
+    // Parses an element. The namespaces declared by the ancestors are passed
+    // as pairs of prefix (empty for the default namespace) and name.
+    fn element(
+        &mut self,
+        depth: usize,
+        namespaces: &[(&'a str, Cow<'a, str>)],
+    ) -> Result<Element<'a>, usize> {
+        if depth > MAX_DEPTH || !self.rest().starts_with('<') {
+            return Err(self.offset);
+        }
+        self.offset += 1;
+        let qualified_name = self.name()?;
+        let (prefix, name) = match qualified_name.find(':') {
+            Some(index) => (&qualified_name[..index], &qualified_name[index + 1..]),
+            None => ("", qualified_name),
+        };
+        let mut namespaces = namespaces.to_vec();
+        let is_empty = loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.offset += 2;
+                break true;
+            } else if rest.starts_with('>') {
+                self.offset += 1;
+                break false;
+            }
+            let (key, value) = self.attribute()?;
+            if key == "xmlns" {
+                namespaces.push(("", value));
+            } else if key.starts_with("xmlns:") {
+                namespaces.push((&key["xmlns:".len()..], value));
+            }
+        };
+        let namespace = match namespaces.iter().rev().find(|(name, _)| *name == prefix) {
+            Some((_, namespace)) if !namespace.is_empty() => Some(namespace.clone()),
+            // Undeclared prefixes are an error, the default namespace is optional.
+            None if !prefix.is_empty() => return Err(self.offset),
+            _ => None,
+        };
+        if is_empty {
+            return Ok(Element {
+                name,
+                namespace,
+                children: Vec::new(),
+                text: Cow::Borrowed(""),
+            });
+        }
+
+        let mut children = Vec::new();
+        let mut text: Option<Cow<'a, str>> = None;
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.offset += 2;
+                if self.name()? != qualified_name {
+                    return Err(self.offset);
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err(self.offset);
+                }
+                self.offset += 1;
+                return Ok(Element {
+                    name,
+                    namespace,
+                    children,
+                    text: text.unwrap_or(Cow::Borrowed("")),
+                });
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.offset += "<![CDATA[".len();
+                let data = self.skip_past("]]>")?;
+                append(&mut text, Cow::Borrowed(data));
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                children.push(self.element(depth + 1, &namespaces)?);
+            } else if rest.is_empty() {
+                return Err(self.offset);
+            } else {
+                let len = rest.find('<').unwrap_or(rest.len());
+                let data = decode(&rest[..len]).map_err(|index| self.offset + index)?;
+                self.offset += len;
+                append(&mut text, data);
+            }
+        }
+    }
+
+    fn attribute(&mut self) -> Result<(&'a str, Cow<'a, str>), usize> {
+        let key = self.name()?;
+        self.skip_whitespace();
+        if !self.rest().starts_with('=') {
+            return Err(self.offset);
+        }
+        self.offset += 1;
+        self.skip_whitespace();
+        let quote = match self.rest().chars().next() {
+            Some(quote @ '"') | Some(quote @ '\'') => quote,
+            _ => return Err(self.offset),
+        };
+        self.offset += 1;
+        let start = self.offset;
+        let value = self.skip_past(if quote == '"' { "\"" } else { "'" })?;
+        if value.contains('<') {
+            return Err(self.offset);
+        }
+        let value = decode(value).map_err(|index| start + index)?;
+        Ok((key, value))
+    }
+}
+
+fn append<'a>(text: &mut Option<Cow<'a, str>>, data: Cow<'a, str>) {
+    match text {
+        Some(text) => text.to_mut().push_str(&data),
+        None => *text = Some(data),
+    }
+}
+
+// Decodes the predefined and numeric entities. On error, the index of the
+// invalid entity is returned.
+fn decode(input: &str) -> Result<Cow<'_, str>, usize> {
+    if !input.contains('&') {
+        return Ok(Cow::Borrowed(input));
+    }
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        let index = input.len() - rest.len() + start;
+        let end = rest[start..].find(';').ok_or(index)?;
+        let entity = &rest[start + 1..start + end];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ if entity.starts_with("#x") => u32::from_str_radix(&entity[2..], 16)
+                .ok()
+                .and_then(std::char::from_u32)
+                .ok_or(index)?,
+            _ if entity.starts_with('#') => entity[1..]
+                .parse()
+                .ok()
+                .and_then(std::char::from_u32)
+                .ok_or(index)?,
+            _ => return Err(index),
+        };
+        output.push(c);
+        rest = &rest[start + end + 1..];
+    }
+    output.push_str(rest);
+    Ok(Cow::Owned(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Escape};
+
+    #[test]
+    fn read() {
+        let root = parse(concat!(
+            "<?xml version=\"1.0\"?>\n<!-- comment -->\n",
+            "<x:a xmlns:x='urn:x'><b>1 &lt; 2 &#x26;<![CDATA[<&>]]></b><c/>\n</x:a>"
+        ))
+        .unwrap();
+        assert_eq!(root.name, "a");
+        assert_eq!(root.namespace, Some("urn:x".into()));
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].namespace, None);
+        assert_eq!(root.children[0].text, "1 < 2 &<&>");
+        assert_eq!(root.children[1].text, "");
+
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("<a>&unknown;</a>").is_err());
+        assert!(parse("<a></a><b></b>").is_err());
+        assert!(parse("<!DOCTYPE a><a></a>").is_err());
+        assert!(parse("<x:a></x:a>").is_err());
+    }
+
+    #[test]
+    fn namespaces() {
+        let root =
+            parse("<a xmlns='urn:a'><b xmlns:c=\"urn:c\"><c:d/><e xmlns=''/></b></a>").unwrap();
+        assert_eq!(root.namespace, Some("urn:a".into()));
+        let b = &root.children[0];
+        assert_eq!(b.namespace, Some("urn:a".into()));
+        assert_eq!(b.children[0].name, "d");
+        assert_eq!(b.children[0].namespace, Some("urn:c".into()));
+        assert_eq!(b.children[1].namespace, None);
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(
+            Escape("<a & \"b\">").to_string(),
+            "&lt;a &amp; &quot;b&quot;&gt;"
+        );
+        assert_eq!(
+            Escape("a\u{0}b\tc\r\n\u{1b}").to_string(),
+            "a\u{fffd}b\tc&#13;\n\u{fffd}"
+        );
+    }
+}
@@ -1,7 +1,9 @@
-use ics::parameters::{FmtType, Related, Value};
+use ics::components::{Parameter, Property};
+use ics::parameters::{FmtType, Related, Value, CN};
 use ics::properties::{
-    Attach, Attendee, Categories, Class, Completed, Description, DtEnd, DtStart, Due, FreeBusyTime,
-    LastModified, Organizer, Priority, RRule, Status, Summary, Transp, Trigger, TzName, URL,
+    Attach, Attendee, Categories, Class, Completed, Description, DtEnd, DtStart, Due, ExDate,
+    FreeBusyTime, LastModified, Organizer, Priority, RRule, Resources, Status, Summary, Transp,
+    Trigger, TzName, URL,
 };
 use ics::{escape_text, Alarm, Daylight, Event, FreeBusy, Journal, Standard, TimeZone, ToDo};
 
@@ -159,3 +161,59 @@ fn alarm() {
 
     assert_eq!(alarm.to_string(), expected);
 }
+
+#[test]
+fn lists() {
+    let expected = "BEGIN:VEVENT\r\n\
+                    UID:b68378cf-872d-44f1-9703-5e3725c56e71\r\n\
+                    DTSTAMP:19970901T130000Z\r\n\
+                    CATEGORIES:APPOINTMENT,EDUCATION\r\n\
+                    RESOURCES:EASEL,PROJECTOR\\, HDMI\r\n\
+                    EXDATE:19960402T010000Z,19960403T010000Z,19960404T010000Z\r\n\
+                    END:VEVENT\r\n";
+
+    let mut event = Event::new("b68378cf-872d-44f1-9703-5e3725c56e71", "19970901T130000Z");
+    event.push(Categories::from_list(vec!["APPOINTMENT", "EDUCATION"]).unwrap());
+    let resources = Resources::from_list(vec!["EASEL", "PROJECTOR, HDMI"]).unwrap();
+    assert_eq!(resources.values(), vec!["EASEL", "PROJECTOR, HDMI"]);
+    event.push(resources);
+    let exdate = ExDate::from_list(vec![
+        "19960402T010000Z",
+        "19960403T010000Z",
+        "19960404T010000Z",
+    ])
+    .unwrap();
+    assert_eq!(exdate.values().len(), 3);
+    event.push(exdate);
+
+    assert_eq!(event.to_string(), expected);
+    assert!(ExDate::from_list(Vec::<&str>::new()).is_none());
+}
+
+#[test]
+fn binary() {
+    let attach = Attach::from_bytes(b"Hello", "text/plain");
+    assert_eq!(attach.decode(), Some(b"Hello".to_vec()));
+
+    let mut attach = Attach::new("SGVsbG8=");
+    attach.add(Parameter::new("encoding", "base64"));
+    attach.add(Parameter::new("value", "binary"));
+    assert_eq!(attach.decode(), Some(b"Hello".to_vec()));
+}
+
+#[test]
+fn quoted_parameter() {
+    let mut attendee = Attendee::new("mailto:jsmith@example.com");
+    attendee.add(CN::new("\"Johnny\""));
+    let property = Property::from(attendee);
+    assert_eq!(
+        property.parameter("CN").unwrap().values(),
+        vec!["\"Johnny\""]
+    );
+
+    #[cfg(feature = "rfc6868")]
+    let expected = "ATTENDEE;CN=\"^'Johnny^'\":mailto:jsmith@example.com\r\n";
+    #[cfg(not(feature = "rfc6868"))]
+    let expected = "ATTENDEE;CN=Johnny:mailto:jsmith@example.com\r\n";
+    assert_eq!(property.to_string(), expected);
+}
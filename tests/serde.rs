@@ -0,0 +1,45 @@
+#![cfg(feature = "serde")]
+use ics::components::{Component, Parameter, Property};
+use ics::parameters::TzIDParam;
+use ics::properties::{DtStart, Summary, Trigger};
+use ics::{Alarm, Event, ICalendar};
+
+#[test]
+fn property() {
+    let mut property = Property::new("DTSTART", "20190101T100000");
+    property.add(TzIDParam::new("Europe/Berlin"));
+    let json = serde_json::to_string(&property).unwrap();
+    assert_eq!(
+        json,
+        r#"{"key":"DTSTART","value":"20190101T100000","parameters":{"TZID":"Europe/Berlin"}}"#
+    );
+    assert_eq!(serde_json::from_str::<Property>(&json).unwrap(), property);
+
+    let parameter = Parameter::new("TZID", "Europe/Berlin");
+    let json = serde_json::to_string(&parameter).unwrap();
+    assert_eq!(serde_json::from_str::<Parameter>(&json).unwrap(), parameter);
+}
+
+#[test]
+fn icalendar() {
+    let mut event = Event::new("b68378cf-872d-44f1-9703-5e3725c56e71", "19960704T120000Z");
+    event.push(DtStart::new("19960918T143000Z"));
+    event.push(Summary::new("Networld+Interop Conference"));
+    event.add_alarm(Alarm::audio(Trigger::new("-PT15M")));
+    let mut calendar = ICalendar::new("2.0", "-//xyz Corp//NONSGML PDA Calendar Version 1.0//EN");
+    calendar.add_event(event.clone());
+
+    let json = serde_json::to_string(&calendar).unwrap();
+    let deserialized: ICalendar = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.to_string(), calendar.to_string());
+    assert_eq!(deserialized, calendar);
+
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+
+    let json = serde_json::to_string(&Component::from(calendar.clone())).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Component>(&json).unwrap(),
+        Component::from(calendar)
+    );
+}